@@ -1,13 +1,89 @@
 use anyhow::anyhow;
-use reqwest::Url;
+use log::debug;
+use reqwest::{RequestBuilder, StatusCode, Url};
 use serde::{Deserialize, Serialize};
 use solana_sdk::bs58;
 use solana_sdk::signature::Signer;
+use std::fmt;
+use std::time::Duration;
+use tokio::time::sleep;
 
 const SIGNIN_MSG: &str = "Sign in to GenesysGo Shadow Platform.";
 const PORTAL_SIGNIN_URL: &str = "https://portal.genesysgo.net/api/signin";
 const RPC_SIGNIN_URL: &str = "https://portal.genesysgo.net/api/premium/token";
 
+/// Maximum number of attempts made for each auth request before giving up.
+const MAX_AUTH_ATTEMPTS: u32 = 4;
+/// Delay before the first retry. Doubled after every failed attempt.
+const INITIAL_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Errors that can occur while authenticating against GenesysGo.
+#[derive(Debug)]
+pub enum AuthError {
+    /// The auth server could not be reached, even after retrying.
+    Network(reqwest::Error),
+    /// The auth server rejected the signed challenge or bearer token (HTTP 401/403).
+    /// Usually this means the signer is not whitelisted for the account.
+    Unauthorized { status: u16, body: String },
+    /// The auth server responded with some other 4xx status.
+    ClientError { status: u16, body: String },
+    /// The auth server responded with a 5xx status, even after retrying.
+    ServerError { status: u16, body: String },
+    /// The auth server responded successfully, but with an unexpected body.
+    InvalidResponse(serde_json::Error),
+    /// A request URL could not be constructed.
+    InvalidUrl(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::Network(e) => write!(f, "failed to reach GenesysGo auth server: {}", e),
+            AuthError::Unauthorized { status, body } => write!(
+                f,
+                "GenesysGo auth server rejected the signature or token ({}): {}",
+                status, body
+            ),
+            AuthError::ClientError { status, body } => {
+                write!(
+                    f,
+                    "GenesysGo auth request was invalid ({}): {}",
+                    status, body
+                )
+            }
+            AuthError::ServerError { status, body } => {
+                write!(f, "GenesysGo auth server error ({}): {}", status, body)
+            }
+            AuthError::InvalidResponse(e) => {
+                write!(f, "unexpected response from GenesysGo auth server: {}", e)
+            }
+            AuthError::InvalidUrl(e) => write!(f, "invalid GenesysGo auth url: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AuthError::Network(e) => Some(e),
+            AuthError::InvalidResponse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for AuthError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Network(e)
+    }
+}
+
+impl From<serde_json::Error> for AuthError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::InvalidResponse(e)
+    }
+}
+
 /// The request body for GenesysGo Portal/Network Authentication.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenesysGoAuth {
@@ -47,7 +123,7 @@ pub struct TokenResponse {
 ///
 /// This function makes two requests. Its first request acquires a GenesysGo Network auth token,
 /// which it then uses to acquire the RPC auth token.
-pub async fn authenticate(signer: &dyn Signer, account_id: &str) -> anyhow::Result<String> {
+pub async fn authenticate(signer: &dyn Signer, account_id: &str) -> Result<String, AuthError> {
     let client = reqwest::Client::new();
     let resp = genesysgo_portal_auth(signer, &client).await?;
     let resp = genesysgo_rpc_auth(account_id, &resp.token, &client).await?;
@@ -56,44 +132,106 @@ pub async fn authenticate(signer: &dyn Signer, account_id: &str) -> anyhow::Resu
 
 /// Authenticate to the GenesysGo Portal/Network. If your ultimate aim is
 /// to get an auth token for RPC, this is the first step.
+///
+/// Network failures and 5xx responses are retried with exponential backoff.
 pub async fn genesysgo_portal_auth(
     signer: &dyn Signer,
     client: &reqwest::Client,
-) -> anyhow::Result<GenesysGoAuthResponse> {
+) -> Result<GenesysGoAuthResponse, AuthError> {
     let signature = signer.sign_message(SIGNIN_MSG.as_bytes());
     let body = GenesysGoAuth {
         message: bs58::encode(signature.as_ref()).into_string(),
         signer: signer.pubkey().to_string(),
     };
-    let resp = client
-        .post(Url::parse(PORTAL_SIGNIN_URL)?)
-        .header("Content-Type", "application/json")
-        .body(serde_json::to_string(&body)?)
-        .send()
-        .await?;
-    let auth_resp: GenesysGoAuthResponse = serde_json::from_str(&resp.text().await?)?;
+    let url = Url::parse(PORTAL_SIGNIN_URL).map_err(|e| AuthError::InvalidUrl(e.to_string()))?;
+    let body = serde_json::to_string(&body)?;
+    let resp = send_with_retries(|| {
+        client
+            .post(url.clone())
+            .header("Content-Type", "application/json")
+            .body(body.clone())
+    })
+    .await?;
+    let auth_resp: GenesysGoAuthResponse = serde_json::from_str(&resp)?;
     Ok(auth_resp)
 }
 
 /// Using the bearer token acquired from [genesysgo_portal_auth],
 /// acquire an RPC auth token for a GenesysGo Premium RPC account based on its Account ID.
+///
+/// Network failures and 5xx responses are retried with exponential backoff.
 pub async fn genesysgo_rpc_auth(
     account_id: &str,
     step_1_auth_token: &str,
     client: &reqwest::Client,
-) -> anyhow::Result<TokenResponse> {
-    let step2_url = RPC_SIGNIN_URL.to_owned() + "/" + account_id;
+) -> Result<TokenResponse, AuthError> {
+    let step2_url = Url::parse(&(RPC_SIGNIN_URL.to_owned() + "/" + account_id))
+        .map_err(|e| AuthError::InvalidUrl(e.to_string()))?;
     let bearer_token = format!("Bearer {}", step_1_auth_token);
-    let resp = client
-        .post(Url::parse(&step2_url)?)
-        .header("Content-Type", "application/json")
-        .header("Authorization", &bearer_token)
-        .send()
-        .await?;
-    let resp: TokenResponse = serde_json::from_str(&resp.text().await?)?;
+    let resp = send_with_retries(|| {
+        client
+            .post(step2_url.clone())
+            .header("Content-Type", "application/json")
+            .header("Authorization", &bearer_token)
+    })
+    .await?;
+    let resp: TokenResponse = serde_json::from_str(&resp)?;
     Ok(resp)
 }
 
+/// Sends the request produced by `build`, retrying on network failures and 5xx
+/// responses. Returns the response body on success.
+async fn send_with_retries<F>(build: F) -> Result<String, AuthError>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut backoff = INITIAL_RETRY_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let err = match build().send().await {
+            Ok(resp) => {
+                let status = resp.status();
+                let body = resp.text().await?;
+                if status.is_success() {
+                    return Ok(body);
+                }
+                status_error(status, body)
+            }
+            Err(e) => AuthError::Network(e),
+        };
+
+        let retryable = matches!(err, AuthError::Network(_) | AuthError::ServerError { .. });
+        if !retryable || attempt >= MAX_AUTH_ATTEMPTS {
+            return Err(err);
+        }
+        debug!(
+            "GenesysGo auth attempt {} of {} failed: {}, retrying in {:?}",
+            attempt, MAX_AUTH_ATTEMPTS, err, backoff
+        );
+        sleep(backoff).await;
+        backoff *= 2;
+        attempt += 1;
+    }
+}
+
+fn status_error(status: StatusCode, body: String) -> AuthError {
+    let status_code = status.as_u16();
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AuthError::Unauthorized {
+            status: status_code,
+            body,
+        },
+        s if s.is_server_error() => AuthError::ServerError {
+            status: status_code,
+            body,
+        },
+        _ => AuthError::ClientError {
+            status: status_code,
+            body,
+        },
+    }
+}
+
 /// If you only have a GenesysGo RPC URL, this will parse out
 /// the account ID necessary to perform a sign-in.
 pub fn parse_account_id_from_url(genesysgo_url: String) -> anyhow::Result<String> {
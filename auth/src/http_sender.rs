@@ -1,6 +1,7 @@
 /// Copied from `solana-rpc-client` crate, modified [HttpSender]
 /// to allow for passing in default headers. This is useful for
 /// passing auth headers to RPC services like GenesysGo.
+use crate::provider::{bearer_headers, AuthProvider};
 use reqwest::header::HeaderMap;
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -35,6 +36,9 @@ pub struct RpcErrorObject {
 pub struct HttpSenderWithHeaders {
     client: Arc<reqwest::Client>,
     url: String,
+    /// Custom headers, applied explicitly to every request (including retries)
+    /// rather than relying solely on the client's default headers.
+    headers: HeaderMap,
     request_id: AtomicU64,
     stats: RwLock<RpcTransportStats>,
 }
@@ -65,9 +69,8 @@ impl HttpSenderWithHeaders {
             )
             .unwrap(),
         );
-        if let Some(headers) = headers {
-            default_headers.extend(headers);
-        }
+        let headers = headers.unwrap_or_default();
+        default_headers.extend(headers.clone());
 
         let client = Arc::new(
             reqwest::Client::builder()
@@ -81,12 +84,27 @@ impl HttpSenderWithHeaders {
         Self {
             client,
             url: url.to_string(),
+            headers,
             request_id: AtomicU64::new(0),
             stats: RwLock::new(RpcTransportStats::default()),
         }
     }
 }
 
+impl HttpSenderWithHeaders {
+    /// Create an HTTP RPC sender that authenticates every request with a bearer
+    /// token acquired from `provider`.
+    ///
+    /// The token is acquired once, when the sender is created.
+    pub async fn from_provider<U: ToString>(
+        url: U,
+        provider: &dyn AuthProvider,
+    ) -> anyhow::Result<Self> {
+        let token = provider.token().await?;
+        Ok(Self::new(url, Some(bearer_headers(&token)?)))
+    }
+}
+
 struct StatsUpdater<'a> {
     stats: &'a RwLock<RpcTransportStats>,
     request_start_time: Instant,
@@ -149,6 +167,7 @@ impl RpcSender for HttpSenderWithHeaders {
                 let request_json = request_json.clone();
                 client
                     .post(&self.url)
+                    .headers(self.headers.clone())
                     .header(CONTENT_TYPE, "application/json")
                     .body(request_json)
                     .send()
@@ -251,4 +270,82 @@ mod tests {
             .send(RpcRequest::GetVersion, serde_json::Value::Null)
             .await;
     }
+
+    /// Serves `responses` in order, one per connection, recording the
+    /// `Authorization` header (if any) of each request.
+    async fn spawn_recording_server(
+        responses: Vec<&'static str>,
+    ) -> (String, Arc<std::sync::Mutex<Vec<Option<String>>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen_by_server = seen.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 1024];
+                // Read until the end of the headers and the full body
+                loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                    let request = String::from_utf8_lossy(&buf).to_string();
+                    if let Some(end) = request.find("\r\n\r\n") {
+                        let content_length = request
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if buf.len() >= end + 4 + content_length || n == 0 {
+                            let auth = request.lines().find_map(|l| {
+                                l.to_lowercase()
+                                    .starts_with("authorization:")
+                                    .then(|| l["authorization:".len()..].trim().to_string())
+                            });
+                            seen_by_server.lock().unwrap().push(auth);
+                            break;
+                        }
+                    }
+                }
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+        });
+        (url, seen)
+    }
+
+    const OK_RESPONSE: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 56\r\nConnection: close\r\n\r\n{\"jsonrpc\":\"2.0\",\"result\":{\"solana-core\":\"1.14\"},\"id\":0}";
+    const TOO_MANY_REQUESTS_RESPONSE: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn http_sender_sends_headers_on_every_request() {
+        let (url, seen) = spawn_recording_server(vec![
+            OK_RESPONSE,
+            TOO_MANY_REQUESTS_RESPONSE,
+            OK_RESPONSE,
+            OK_RESPONSE,
+        ])
+        .await;
+        let headers = bearer_headers("test-token").unwrap();
+        let http_sender = HttpSenderWithHeaders::new(url, Some(headers));
+
+        for _ in 0..3 {
+            http_sender
+                .send(RpcRequest::GetVersion, serde_json::Value::Null)
+                .await
+                .unwrap();
+        }
+
+        let seen = seen.lock().unwrap();
+        // Three requests, one of which was retried after a 429
+        assert_eq!(seen.len(), 4);
+        for auth in seen.iter() {
+            assert_eq!(auth.as_deref(), Some("Bearer test-token"));
+        }
+    }
 }
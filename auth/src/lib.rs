@@ -1,7 +1,9 @@
 pub mod genesysgo_auth;
 pub mod http_sender;
+pub mod provider;
 
 pub use genesysgo_auth::{
-    authenticate, genesysgo_portal_auth, genesysgo_rpc_auth, parse_account_id_from_url,
+    authenticate, genesysgo_portal_auth, genesysgo_rpc_auth, parse_account_id_from_url, AuthError,
 };
 pub use http_sender::HttpSenderWithHeaders;
+pub use provider::{bearer_headers, AuthProvider, GenesysGoAuthProvider, StaticTokenProvider};
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use solana_sdk::signature::Signer;
+
+use crate::genesysgo_auth::authenticate;
+
+/// A source of bearer tokens for RPC providers that require auth headers.
+///
+/// Implement this for any provider or gateway that hands out bearer tokens,
+/// then use [HttpSenderWithHeaders::from_provider](crate::HttpSenderWithHeaders::from_provider)
+/// to build an authenticated RPC sender.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Acquire a bearer token. Implementations may perform network requests.
+    async fn token(&self) -> anyhow::Result<String>;
+}
+
+/// An [AuthProvider] for a token that was acquired out of band, e.g. a JWT
+/// passed on the command line.
+pub struct StaticTokenProvider {
+    token: String,
+}
+
+impl StaticTokenProvider {
+    pub fn new<T: ToString>(token: T) -> Self {
+        Self {
+            token: token.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for StaticTokenProvider {
+    async fn token(&self) -> anyhow::Result<String> {
+        Ok(self.token.clone())
+    }
+}
+
+/// An [AuthProvider] that signs in to a GenesysGo Premium RPC account.
+/// The signer must be whitelisted for the account.
+pub struct GenesysGoAuthProvider<S: Signer + Send + Sync> {
+    signer: S,
+    account_id: String,
+}
+
+impl<S: Signer + Send + Sync> GenesysGoAuthProvider<S> {
+    pub fn new<T: ToString>(signer: S, account_id: T) -> Self {
+        Self {
+            signer,
+            account_id: account_id.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Signer + Send + Sync> AuthProvider for GenesysGoAuthProvider<S> {
+    async fn token(&self) -> anyhow::Result<String> {
+        Ok(authenticate(&self.signer, &self.account_id).await?)
+    }
+}
+
+/// Build a [HeaderMap] containing an `Authorization: Bearer <token>` header.
+pub fn bearer_headers(token: &str) -> anyhow::Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", token))?,
+    );
+    Ok(headers)
+}
@@ -1,15 +1,33 @@
+use crate::encryption::{encrypted_shadow_file, encryption_key_arg, EncryptionKey};
 use crate::utils::{
-    get_text, last_modified, parse_filesize, process_shadow_api_response, pubkey_arg,
-    shadow_client_factory, shadow_file_with_basename, storage_object_url,
-    wait_for_user_confirmation, FileMetadata, FILE_UPLOAD_BATCH_SIZE,
+    directory_size, parse_filesize, print_dry_run_notice, print_estimated_cost,
+    process_shadow_api_response, pubkey_arg, resolve_storage_account, shadow_client_factory,
+    storage_account_arg, storage_object_url, wait_for_finalization, wait_for_user_confirmation,
+    OutputFormat, StorageAccountArg, FILE_UPLOAD_BATCH_SIZE,
 };
+use anyhow::anyhow;
 use byte_unit::Byte;
+use chrono::{TimeZone, Utc};
 use clap::Parser;
 use futures::StreamExt;
-use shadow_drive_sdk::{Pubkey, ShadowDriveClient, StorageAccountVersion};
+use serde_json::json;
+use shadow_drive_sdk::models::{FileMetadata, ShadowFile};
+use shadow_drive_sdk::{
+    MirrorOptions, ObjectFilter, OperationKind, Pubkey, RetryPolicy, StorageAccountFilter,
+    StorageAccountVersion,
+};
 use shadow_rpc_auth::genesysgo_auth::{authenticate, parse_account_id_from_url};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::lamports_to_sol;
 use solana_sdk::signature::Signer;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How often to check the current epoch when waiting to claim stake.
+const CLAIM_STAKE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// Maximum number of file metadata requests in flight when building a manifest.
+const MANIFEST_CONCURRENCY: usize = 16;
 
 #[derive(Debug, Parser)]
 pub enum DriveCommand {
@@ -22,7 +40,7 @@ pub enum DriveCommand {
     CreateStorageAccount {
         /// Unique identifier for your storage account
         name: String,
-        /// File size string, accepts KB, MB, GB, e.g. "10MB"
+        /// File size string, accepts KB, MB, GB (binary units), e.g. "10MB"
         #[clap(parse(try_from_str = parse_filesize))]
         size: Byte,
     },
@@ -31,59 +49,96 @@ pub enum DriveCommand {
     /// can be made (see cancel-delete-storage-account subcommand).
     DeleteStorageAccount {
         /// The account to delete
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Print what would be deleted without deleting anything.
+        #[clap(long)]
+        dry_run: bool,
     },
     /// Cancels the deletion of a storage account enqueued for deletion.
     CancelDeleteStorageAccount {
         /// The account for which to cancel deletion.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
     },
     /// Redeem tokens afforded to a storage account after reducing storage capacity.
     ClaimStake {
         /// The account whose stake to claim.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Wait for the unstake cooldown to elapse (end of the epoch in which
+        /// storage was reduced) before claiming.
+        #[clap(long)]
+        wait: bool,
+        /// Maximum number of seconds to wait when using --wait.
+        #[clap(long, default_value_t = 3 * 24 * 60 * 60, requires = "wait")]
+        wait_timeout: u64,
+    },
+    /// Top off the stake of a storage account, and unmark it for deletion.
+    RefreshStake {
+        /// The account whose stake to refresh.
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Additional SHDW, in shades, to transfer into the stake account.
+        #[clap(long)]
+        amount: Option<u64>,
+    },
+    /// Collect outstanding mutable storage fees from one or more storage
+    /// accounts. Anyone may crank any account.
+    Crank {
+        /// The accounts to crank.
+        #[clap(parse(try_from_str = storage_account_arg), min_values = 1)]
+        storage_accounts: Vec<StorageAccountArg>,
+        /// Maximum number of cranks to run at once. Defaults to the global
+        /// `--concurrency`, or 4 if that isn't set either.
+        #[clap(long)]
+        concurrency: Option<usize>,
     },
     /// Increase the capacity of a storage account.
     AddStorage {
         /// Storage account to modify
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
-        /// File size string, accepts KB, MB, GB, e.g. "10MB"
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// File size string, accepts KB, MB, GB (binary units), e.g. "10MB"
         #[clap(parse(try_from_str = parse_filesize))]
         size: Byte,
     },
     /// Increase the immutable storage capacity of a storage account.
     AddImmutableStorage {
         /// Storage account to modify
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
-        /// File size string, accepts KB, MB, GB, e.g. "10MB"
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// File size string, accepts KB, MB, GB (binary units), e.g. "10MB"
         #[clap(parse(try_from_str = parse_filesize))]
         size: Byte,
     },
     /// Reduce the capacity of a storage account.
     ReduceStorage {
         /// Storage account to modify
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
-        /// File size string, accepts KB, MB, GB, e.g. "10MB"
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// File size string, accepts KB, MB, GB (binary units), e.g. "10MB"
         #[clap(parse(try_from_str = parse_filesize))]
         size: Byte,
     },
     /// Make a storage account immutable. This is irreversible.
     MakeStorageImmutable {
         /// Storage account to be marked immutable
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+    },
+    /// Migrate a v1 storage account to v2. Resumes a migration
+    /// that was interrupted after its first step.
+    Migrate {
+        /// The account to migrate.
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
     },
     /// Fetch the metadata pertaining to a storage account.
     GetStorageAccount {
         /// Account whose metadata will be fetched.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
     },
     /// Fetch a list of storage accounts owned by a particular pubkey.
     /// If no owner is provided, the configured signer is used.
@@ -91,46 +146,167 @@ pub enum DriveCommand {
         /// Searches for storage accounts owned by this owner.
         #[clap(parse(try_from_str = pubkey_arg))]
         owner: Option<Pubkey>,
+        /// Only list accounts that are not marked for deletion.
+        #[clap(long, conflicts_with = "only-pending-deletion")]
+        only_active: bool,
+        /// Only list accounts that are marked for deletion.
+        #[clap(long)]
+        only_pending_deletion: bool,
+        /// List accounts whether or not they are marked for deletion. This is
+        /// the default; the flag makes it explicit, e.g. in scripts.
+        #[clap(long, conflicts_with_all = &["only-active", "only-pending-deletion"])]
+        include_deleted: bool,
+    },
+    /// Find the signer's empty, mutable storage accounts and delete
+    /// them on confirmation to reclaim their stake.
+    Prune {
+        /// Print what would be deleted without deleting anything.
+        #[clap(long)]
+        dry_run: bool,
     },
     /// List all the files in a storage account.
     ListFiles {
         /// Storage account whose files to list.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Only list files whose name starts with this prefix, e.g. "images/".
+        #[clap(long)]
+        prefix: Option<String>,
+        /// Only list files with this extension, e.g. "json".
+        #[clap(long)]
+        extension: Option<String>,
+        /// After the initial listing, keep polling the account and print
+        /// files as they are added (+) or removed (-), until interrupted.
+        #[clap(long, alias = "follow")]
+        watch: bool,
+        /// Seconds between polls in --watch mode.
+        #[clap(long, default_value_t = 5, requires = "watch")]
+        interval: u64,
+        /// Output format. `jsonl` prints one `{"name": ...}` object per line
+        /// as the listing is received, without buffering it.
+        #[clap(long, arg_enum, default_value = "text")]
+        output: OutputFormat,
     },
     /// Get a file, assume it's text, and print it.
     GetText {
         /// Storage account where the file is located.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
         /// Name of the file to fetch
         filename: String,
     },
+    /// Check that each of the given files of a deployed site can be
+    /// fetched as non-empty text. Fails if any of them cannot.
+    VerifySite {
+        /// Storage account where the site is deployed.
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Names of the files that must be present, e.g. "index.html".
+        #[clap(min_values = 1)]
+        files: Vec<String>,
+    },
+    /// Download a file uploaded with `store-files --encrypt` and decrypt it.
+    Decrypt {
+        /// Storage account where the file is located.
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Name of the file to fetch.
+        filename: String,
+        /// The key the file was encrypted with: a hex or base58 string,
+        /// or the path of a file containing the key.
+        #[clap(parse(try_from_str = encryption_key_arg))]
+        key: EncryptionKey,
+        /// Where to write the decrypted file.
+        output: PathBuf,
+    },
     /// Get basic file object data from a storage account file.
     GetObjectData {
         /// Storage account where the file is located.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
         /// Name of the file to examine.
         file: String,
     },
+    /// Write a JSON manifest of a storage account, for sharing or archiving:
+    /// its identifier, owners, version and immutability, and the name, size,
+    /// content type and public URL of every file.
+    Manifest {
+        /// The storage account to describe.
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Write the manifest to this file instead of printing it.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the base URL of a storage account's files, for sharing.
+    Share {
+        /// The storage account to share.
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// Also write a QR code of the URL to this PNG file.
+        #[clap(long)]
+        qr: Option<PathBuf>,
+    },
     /// Delete a file from a storage account.
     DeleteFile {
         /// Storage account where the file to delete is located.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
         /// Name of the file to delete.
         filename: String,
+        /// Print what would be deleted without deleting anything.
+        #[clap(long)]
+        dry_run: bool,
     },
     /// Has to be the same name as a previously uploaded file
     EditFile {
         /// Storage account where the file to edit is located.
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
         /// Path to the new version of the file. Must be the same
         /// name as the file you are editing.
         path: PathBuf,
     },
+    /// Print the signer's SOL and SHDW balances, e.g. to check whether
+    /// a storage purchase is affordable before attempting it.
+    Balance {
+        /// Output format.
+        #[clap(long, arg_enum, default_value = "text")]
+        output: OutputFormat,
+    },
+    /// Check that the Solana RPC and the Shadow Drive upload server are
+    /// reachable, and report how long each took to respond.
+    Health,
+    /// Estimate the SHDW cost of reserving storage, either for an explicit
+    /// size or for the contents of a directory. No transaction is sent.
+    EstimateCost {
+        /// File size string, accepts KB, MB, GB (binary units), e.g. "10MB"
+        #[clap(long, parse(try_from_str = parse_filesize), required_unless_present = "directory")]
+        size: Option<Byte>,
+        /// Estimate the cost of storing every file in this directory, recursively.
+        #[clap(long, conflicts_with = "size")]
+        directory: Option<PathBuf>,
+    },
+    /// Upload new and changed files from a local directory to a storage account.
+    /// Paths matched by a `.shadowignore` file (gitignore syntax) are skipped.
+    Sync {
+        /// The storage account to sync the directory to.
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
+        /// The local directory to upload from.
+        directory: PathBuf,
+        /// Also delete remote files that are not present in the local
+        /// directory, making the storage account an exact mirror.
+        #[clap(long)]
+        mirror: bool,
+        /// Also skip paths matched by `.gitignore` files.
+        #[clap(long)]
+        gitignore: bool,
+        /// Print the files that would be uploaded and deleted without
+        /// changing anything.
+        #[clap(long)]
+        dry_run: bool,
+    },
     /// Upload one or more files to a storage account.
     StoreFiles {
         // /// Batch size for file uploads, default 100, only relevant for large
@@ -138,11 +314,17 @@ pub enum DriveCommand {
         // #[clap(long, default_value_t=FILE_UPLOAD_BATCH_SIZE)]
         // batch_size: usize,
         /// The storage account on which to upload the files
-        #[clap(parse(try_from_str = pubkey_arg))]
-        storage_account: Pubkey,
+        #[clap(parse(try_from_str = storage_account_arg))]
+        storage_account: StorageAccountArg,
         /// A list of one or more filepaths, each of which is to be uploaded.
         #[clap(min_values = 1)]
         files: Vec<PathBuf>,
+        /// Encrypt each file with AES-256-GCM before uploading, using this key:
+        /// a 32-byte hex or base58 string, or the path of a file containing the key.
+        /// The key is not stored anywhere; keeping it is your responsibility, and
+        /// files cannot be recovered without it. See the `decrypt` subcommand.
+        #[clap(long, parse(try_from_str = encryption_key_arg))]
+        encrypt: Option<EncryptionKey>,
     },
 }
 
@@ -153,11 +335,22 @@ impl DriveCommand {
         client_signer: T,
         rpc_url: &str,
         skip_confirm: bool,
+        wait_finalized: bool,
+        priority_fee: u64,
+        retry_policy: RetryPolicy,
+        commitment: CommitmentConfig,
+        concurrency: Option<usize>,
         auth: Option<String>,
     ) -> anyhow::Result<()> {
         let signer_pubkey = signer.pubkey();
         println!("Signing with {:?}", signer_pubkey);
         println!("Sending RPC requests to {}", rpc_url);
+        if priority_fee > 0 {
+            println!(
+                "Priority fee: {} micro-lamports per compute unit",
+                priority_fee
+            );
+        }
         match self {
             DriveCommand::ShadowRpcAuth => {
                 let account_id = parse_account_id_from_url(rpc_url.to_string())?;
@@ -165,53 +358,167 @@ impl DriveCommand {
                 println!("{:#?}", resp);
             }
             DriveCommand::CreateStorageAccount { name, size } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
                 println!("Create Storage Account {}: {}", name, size);
+                match client.is_identifier_used(&signer_pubkey, name).await {
+                    Ok(true) => println!(
+                        "WARNING: You already have a storage account named {:?}. \
+                    A second account with the same name will be created.",
+                        name
+                    ),
+                    Ok(false) => {}
+                    Err(e) => println!("Failed to check for existing account names: {:?}", e),
+                }
+                print_estimated_cost(&client, OperationKind::CreateStorageAccount, Some(size))
+                    .await;
                 wait_for_user_confirmation(skip_confirm)?;
                 let response = client
                     .create_storage_account(name, size.clone(), StorageAccountVersion::v2())
                     .await;
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.transaction_signature, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
-            DriveCommand::DeleteStorageAccount { storage_account } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+            DriveCommand::DeleteStorageAccount {
+                storage_account,
+                dry_run,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!("Delete Storage Account {}", storage_account.to_string());
+                if *dry_run {
+                    print_dry_run_notice();
+                    return Ok(());
+                }
                 wait_for_user_confirmation(skip_confirm)?;
                 let response = client.delete_storage_account(storage_account).await;
 
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
             DriveCommand::CancelDeleteStorageAccount { storage_account } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!(
                     "Cancellation of Delete Storage Account {}",
                     storage_account.to_string()
                 );
+                let account =
+                    process_shadow_api_response(client.get_storage_account(storage_account).await)?;
+                if !account.to_be_deleted() {
+                    println!("This account is not marked for deletion, nothing to cancel.");
+                    return Ok(());
+                }
                 wait_for_user_confirmation(skip_confirm)?;
                 let response = client.cancel_delete_storage_account(storage_account).await;
 
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
-            DriveCommand::ClaimStake { storage_account } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+            DriveCommand::ClaimStake {
+                storage_account,
+                wait,
+                wait_timeout,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!(
                     "Claim Stake on Storage Account {}",
                     storage_account.to_string()
                 );
                 wait_for_user_confirmation(skip_confirm)?;
-                let response = client.claim_stake(storage_account).await;
+                let response = if *wait {
+                    println!("Waiting for unstake cooldown to elapse...");
+                    client
+                        .claim_stake_when_ready(
+                            storage_account,
+                            CLAIM_STAKE_POLL_INTERVAL,
+                            Duration::from_secs(*wait_timeout),
+                        )
+                        .await
+                } else {
+                    client.claim_stake(storage_account).await
+                };
 
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
+            DriveCommand::RefreshStake {
+                storage_account,
+                amount,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                println!(
+                    "Refresh Stake on Storage Account {}",
+                    storage_account.to_string()
+                );
+                wait_for_user_confirmation(skip_confirm)?;
+                let response = client.refresh_stake(storage_account, *amount).await;
+
+                let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
+                println!("{:#?}", resp);
+            }
+            DriveCommand::Crank {
+                storage_accounts,
+                concurrency: crank_concurrency,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let mut resolved = Vec::with_capacity(storage_accounts.len());
+                for storage_account in storage_accounts {
+                    resolved.push(
+                        resolve_storage_account(&client, &signer_pubkey, storage_account).await?,
+                    );
+                }
+                let storage_accounts = &resolved;
+                println!("Crank Storage Accounts {:#?}", storage_accounts);
+                wait_for_user_confirmation(skip_confirm)?;
+                let responses = client
+                    .crank_many(
+                        storage_accounts,
+                        crank_concurrency.or(concurrency).unwrap_or(4),
+                    )
+                    .await;
+                for (storage_account, response) in storage_accounts.iter().zip(responses) {
+                    println!("{}", storage_account.to_string());
+                    match process_shadow_api_response(response) {
+                        Ok(resp) => {
+                            wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
+                            println!("{:#?}", resp)
+                        }
+                        Err(e) => println!("Failed to crank: {}", e),
+                    }
+                }
+            }
             DriveCommand::ReduceStorage {
                 storage_account,
                 size,
             } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!(
                     "Reduce Storage Capacity {}: {}",
                     storage_account.to_string(),
@@ -221,25 +528,36 @@ impl DriveCommand {
                 let response = client.reduce_storage(storage_account, size.clone()).await;
 
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.transaction_signature, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
             DriveCommand::AddStorage {
                 storage_account,
                 size,
             } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!("Increase Storage {}: {}", storage_account.to_string(), size);
+                print_estimated_cost(&client, OperationKind::AddStorage, Some(size)).await;
                 wait_for_user_confirmation(skip_confirm)?;
                 let response = client.add_storage(storage_account, size.clone()).await;
 
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.transaction_signature, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
             DriveCommand::AddImmutableStorage {
                 storage_account,
                 size,
             } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!(
                     "Increase Immutable Storage {}: {}",
                     storage_account.to_string(),
@@ -251,62 +569,293 @@ impl DriveCommand {
                     .await;
 
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.transaction_signature, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
             DriveCommand::MakeStorageImmutable { storage_account } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!("Make Storage Immutable {}", storage_account.to_string());
                 wait_for_user_confirmation(skip_confirm)?;
                 let response = client.make_storage_immutable(storage_account).await;
 
                 let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.transaction_signature, wait_finalized).await?;
+                println!("{:#?}", resp);
+            }
+            DriveCommand::Migrate { storage_account } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                println!("Migrate Storage Account {}", storage_account.to_string());
+                let response = client.is_migration_in_progress(storage_account).await;
+                if process_shadow_api_response(response)? {
+                    println!("Step 1 already completed, skipping");
+                } else {
+                    println!("Step 1: move account data to the migration helper");
+                    wait_for_user_confirmation(skip_confirm)?;
+                    let response = client.migrate_step_1(storage_account).await;
+                    let resp = process_shadow_api_response(response)?;
+                    wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
+                    println!("{:#?}", resp);
+                }
+                println!("Step 2: recreate the account as v2");
+                wait_for_user_confirmation(skip_confirm)?;
+                let response = client.migrate_step_2(storage_account).await;
+                let resp = process_shadow_api_response(response)?;
+                wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
                 println!("{:#?}", resp);
             }
             DriveCommand::GetStorageAccount { storage_account } => {
-                let client = ShadowDriveClient::new(client_signer, rpc_url);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 println!("Get Storage Account {}", storage_account.to_string());
                 let response = client.get_storage_account(storage_account).await;
 
                 let act = process_shadow_api_response(response)?;
+                println!("Version: {}", act.version());
                 println!("{:#?}", act);
             }
-            DriveCommand::GetStorageAccounts { owner } => {
-                let client = shadow_client_factory(client_signer, rpc_url, auth.clone());
+            DriveCommand::GetStorageAccounts {
+                owner,
+                only_active,
+                only_pending_deletion,
+                include_deleted,
+            } => {
+                let client =
+                    shadow_client_factory(client_signer, rpc_url, commitment, auth.clone())
+                        .with_retry_policy(retry_policy);
                 let owner = owner.as_ref().unwrap_or(&signer_pubkey);
+                let filter = if *include_deleted {
+                    StorageAccountFilter::All
+                } else if *only_active {
+                    StorageAccountFilter::OnlyActive
+                } else if *only_pending_deletion {
+                    StorageAccountFilter::OnlyPendingDeletion
+                } else {
+                    StorageAccountFilter::All
+                };
                 println!("Get Storage Accounts Owned By {}", owner.to_string());
-                let response = client.get_storage_accounts(owner).await;
+                let response = client.get_storage_accounts(owner, filter).await;
                 let accounts = process_shadow_api_response(response)?;
                 println!("{:#?}", accounts);
             }
-            DriveCommand::ListFiles { storage_account } => {
-                let client = ShadowDriveClient::new(client_signer, rpc_url);
-                println!(
-                    "List Files for Storage Account {}",
-                    storage_account.to_string()
-                );
-                let response = client.list_objects(storage_account).await;
-                let files = process_shadow_api_response(response)?;
-                println!("{:#?}", files);
+            DriveCommand::Prune { dry_run } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                println!("Find Empty Storage Accounts Owned By {}", signer_pubkey);
+                let response = client.list_prunable_accounts(&signer_pubkey).await;
+                let prunable = process_shadow_api_response(response)?;
+                if prunable.is_empty() {
+                    println!("No empty storage accounts found");
+                    return Ok(());
+                }
+                for (storage_account, account) in &prunable {
+                    println!("{} ({})", storage_account, account.identifier());
+                }
+                println!("Delete {} Storage Accounts", prunable.len());
+                if *dry_run {
+                    print_dry_run_notice();
+                    return Ok(());
+                }
+                wait_for_user_confirmation(skip_confirm)?;
+                for (storage_account, _) in &prunable {
+                    let response = client.delete_storage_account(storage_account).await;
+                    let resp = process_shadow_api_response(response)?;
+                    wait_for_finalization(&client, &resp.txid, wait_finalized).await?;
+                    println!("{:#?}", resp);
+                }
+            }
+            DriveCommand::ListFiles {
+                storage_account,
+                prefix,
+                extension,
+                watch,
+                interval,
+                output,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                let mut filter = ObjectFilter::new();
+                if let Some(prefix) = prefix {
+                    filter = filter.prefix(prefix);
+                }
+                if let Some(extension) = extension {
+                    filter = filter.extension(extension);
+                }
+                let mut previous = BTreeSet::new();
+                match output {
+                    OutputFormat::Text => {
+                        println!(
+                            "List Files for Storage Account {}",
+                            storage_account.to_string()
+                        );
+                        let response = client
+                            .list_objects_filtered(storage_account, filter.clone())
+                            .await;
+                        let files = process_shadow_api_response(response)?;
+                        println!("{:#?}", files);
+                        previous.extend(files);
+                    }
+                    OutputFormat::Json => {
+                        let response = client
+                            .list_objects_filtered(storage_account, filter.clone())
+                            .await;
+                        let files = process_shadow_api_response(response)?;
+                        println!("{}", json!(files));
+                        previous.extend(files);
+                    }
+                    OutputFormat::JsonLines => {
+                        let mut files = client.list_objects_stream(storage_account);
+                        while let Some(file) = files.next().await {
+                            let file = process_shadow_api_response(file)?;
+                            if !filter.matches(&file) {
+                                continue;
+                            }
+                            println!("{}", json!({ "name": file }));
+                            if *watch {
+                                previous.insert(file);
+                            }
+                        }
+                    }
+                }
+                if *watch {
+                    if *output == OutputFormat::Text {
+                        println!("Watching for changes every {}s, Ctrl-C to stop", interval);
+                    }
+                    loop {
+                        tokio::select! {
+                            _ = tokio::signal::ctrl_c() => break,
+                            _ = tokio::time::sleep(Duration::from_secs(*interval)) => {}
+                        }
+                        let response = client
+                            .list_objects_filtered(storage_account, filter.clone())
+                            .await;
+                        let current: BTreeSet<String> = match process_shadow_api_response(response)
+                        {
+                            Ok(files) => files.into_iter().collect(),
+                            Err(e) => {
+                                eprintln!("Failed to list files: {}", e);
+                                continue;
+                            }
+                        };
+                        for added in current.difference(&previous) {
+                            match output {
+                                OutputFormat::Text => println!("+ {}", added),
+                                _ => println!("{}", json!({ "name": added, "change": "added" })),
+                            }
+                        }
+                        for removed in previous.difference(&current) {
+                            match output {
+                                OutputFormat::Text => println!("- {}", removed),
+                                _ => {
+                                    println!("{}", json!({ "name": removed, "change": "removed" }))
+                                }
+                            }
+                        }
+                        previous = current;
+                    }
+                }
             }
             DriveCommand::GetText {
                 storage_account,
                 filename,
             } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 let url = storage_object_url(storage_account, filename);
-                let resp = get_text(&url).await?;
-                let last_modified = last_modified(resp.headers())?;
+                let response = client.get_text(storage_account, filename).await;
+                let text = process_shadow_api_response(response)?;
+                let last_modified = Utc
+                    .timestamp_opt(text.metadata.last_modified, 0)
+                    .single()
+                    .map(|last_modified| last_modified.to_rfc2822())
+                    .unwrap_or_else(|| text.metadata.last_modified.to_string());
                 println!("Get Text at {}", &url);
                 println!("Last Modified: {}", last_modified);
                 println!("");
-                println!("{}", resp.text().await?);
+                println!("{}", text.content);
+            }
+            DriveCommand::VerifySite {
+                storage_account,
+                files,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                println!("Verify {} files in {}", files.len(), storage_account);
+                let response = client.get_texts(storage_account, files).await;
+                let texts = process_shadow_api_response(response)?;
+                let mut failures = 0;
+                for file in files {
+                    match &texts[file] {
+                        Ok(text) if text.trim().is_empty() => {
+                            failures += 1;
+                            println!("EMPTY    {}", file);
+                        }
+                        Ok(_) => println!("OK       {}", file),
+                        Err(e) => {
+                            failures += 1;
+                            println!("FAILED   {}: {:?}", file, e);
+                        }
+                    }
+                }
+                if failures > 0 {
+                    return Err(anyhow!(
+                        "{} of {} files failed verification",
+                        failures,
+                        files.len()
+                    ));
+                }
+                println!("All {} files verified", files.len());
+            }
+            DriveCommand::Decrypt {
+                storage_account,
+                filename,
+                key,
+                output,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                let url = storage_object_url(storage_account, filename);
+                println!("Decrypt {} to {}", &url, output.display());
+                let resp = reqwest::get(&url).await?.error_for_status()?;
+                let plaintext = key
+                    .decrypt(&resp.bytes().await?)
+                    .map_err(|e| anyhow!("{:?}", e))?;
+                std::fs::write(output, plaintext)?;
+                println!("Wrote {}", output.display());
             }
             DriveCommand::DeleteFile {
                 storage_account,
                 filename,
+                dry_run,
             } => {
-                let client = ShadowDriveClient::new(client_signer, rpc_url);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 let url = storage_object_url(storage_account, filename);
                 println!("Delete file {}", &url);
+                if *dry_run {
+                    print_dry_run_notice();
+                    return Ok(());
+                }
                 wait_for_user_confirmation(skip_confirm)?;
                 let response = client.delete_file(storage_account, url.clone()).await;
                 let resp = process_shadow_api_response(response)?;
@@ -316,8 +865,11 @@ impl DriveCommand {
                 storage_account,
                 path,
             } => {
-                let client = ShadowDriveClient::new(client_signer, rpc_url);
-                let shadow_file = shadow_file_with_basename(path);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                let shadow_file = process_shadow_api_response(ShadowFile::try_from(path))?;
                 println!(
                     "Edit file {} {}",
                     storage_account.to_string(),
@@ -332,45 +884,247 @@ impl DriveCommand {
                 storage_account,
                 file,
             } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
                 let url = storage_object_url(storage_account, file);
                 println!("Get object data {} {}", storage_account.to_string(), file);
                 let http_client = reqwest::Client::new();
                 let response = http_client.head(url).send().await?;
-                let data = FileMetadata::from_headers(response.headers())?;
+                let data =
+                    process_shadow_api_response(FileMetadata::from_headers(response.headers()))?;
                 println!("{:#?}", data);
             }
-            DriveCommand::StoreFiles {
+            DriveCommand::Manifest {
                 storage_account,
-                files,
+                output,
             } => {
-                let client = ShadowDriveClient::new(client_signer, rpc_url);
-                println!("Store Files {} {:#?}", storage_account.to_string(), files);
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                let account =
+                    process_shadow_api_response(client.get_storage_account(storage_account).await)?;
+                let names =
+                    process_shadow_api_response(client.list_objects(storage_account).await)?;
+                let metadata = process_shadow_api_response(
+                    client
+                        .get_object_data_many(storage_account, &names, MANIFEST_CONCURRENCY)
+                        .await,
+                )?;
+
+                let version = account.version();
+                let mut owners = vec![account.owner_1().to_string()];
+                if let StorageAccountVersion::V1 {
+                    owner_2: Some(owner_2),
+                } = version
+                {
+                    owners.push(owner_2.to_string());
+                }
+                let files: Vec<_> = names
+                    .iter()
+                    .map(|name| {
+                        let data = metadata.get(name);
+                        json!({
+                            "name": name,
+                            "size": data.and_then(|data| data.content_length),
+                            "content_type": data.map(|data| &data.content_type),
+                            "url": storage_object_url(storage_account, name),
+                        })
+                    })
+                    .collect();
+                let manifest = serde_json::to_string_pretty(&json!({
+                    "storage_account": storage_account.to_string(),
+                    "identifier": account.identifier(),
+                    "owners": owners,
+                    "version": version.to_string(),
+                    "immutable": account.is_immutable(),
+                    "storage": account.storage(),
+                    "files": files,
+                }))?;
+                match output {
+                    Some(path) => {
+                        std::fs::write(path, manifest)?;
+                        println!(
+                            "Manifest of {} files written to {}",
+                            files.len(),
+                            path.display()
+                        );
+                    }
+                    None => println!("{}", manifest),
+                }
+            }
+            DriveCommand::Share {
+                storage_account,
+                qr,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                println!("{}", client.storage_account_url(storage_account));
+                if let Some(path) = qr {
+                    std::fs::write(path, client.storage_account_qr(storage_account))?;
+                    println!("QR code written to {}", path.display());
+                }
+            }
+            DriveCommand::Balance { output } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_retry_policy(retry_policy);
+                let lamports =
+                    process_shadow_api_response(client.get_sol_balance(&signer_pubkey).await)?;
+                let shdw =
+                    process_shadow_api_response(client.get_shdw_balance(&signer_pubkey).await)?;
+                match output {
+                    OutputFormat::Text => {
+                        println!("Wallet: {}", signer_pubkey);
+                        println!("SOL balance: {} SOL", lamports_to_sol(lamports));
+                        println!("SHDW balance: {}", shdw);
+                    }
+                    OutputFormat::Json | OutputFormat::JsonLines => println!(
+                        "{}",
+                        json!({
+                            "wallet": signer_pubkey.to_string(),
+                            "sol": lamports_to_sol(lamports),
+                            "lamports": lamports,
+                            "shdw": shdw.as_ui(),
+                            "shades": shdw.to_shades(),
+                        })
+                    ),
+                }
+            }
+            DriveCommand::Health => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth);
+                let start = Instant::now();
+                match client.get_epoch_info().await {
+                    Ok(_) => println!("RPC: ok ({:?})", start.elapsed()),
+                    Err(e) => println!("RPC: unreachable: {:?}", e),
+                }
+                match client.ping_upload_server().await {
+                    Ok(rtt) => println!("Upload server: ok ({:?})", rtt),
+                    Err(e) => println!("Upload server: unreachable: {:?}", e),
+                }
+            }
+            DriveCommand::EstimateCost { size, directory } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, auth)
+                    .with_priority_fee(priority_fee)
+                    .with_retry_policy(retry_policy);
+                let size = match (size, directory) {
+                    (Some(size), _) => size.clone(),
+                    (None, Some(directory)) => Byte::from_bytes(directory_size(directory)? as u128),
+                    (None, None) => return Err(anyhow!("Provide either --size or --directory")),
+                };
+                println!("Estimate Cost of {}", size.get_appropriate_unit(true));
+                let response = client.estimate_storage_cost(size).await;
+                let cost = process_shadow_api_response(response)?;
+                println!("Estimated Cost: {}", cost);
+                let response = client.get_shdw_balance(&signer_pubkey).await;
+                match response {
+                    Ok(balance) => println!("Current Balance: {}", balance),
+                    Err(e) => println!("Failed to fetch SHDW balance: {:?}", e),
+                }
+            }
+            DriveCommand::Sync {
+                storage_account,
+                directory,
+                mirror,
+                gitignore,
+                dry_run,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                println!(
+                    "Sync {} to {}",
+                    directory.display(),
+                    storage_account.to_string()
+                );
+                let options = MirrorOptions {
+                    respect_gitignore: *gitignore,
+                };
+                let response = client
+                    .plan_mirror_with(storage_account, directory, &options)
+                    .await;
+                let mut plan = process_shadow_api_response(response)?;
+                if !*mirror {
+                    plan.deletions.clear();
+                }
+                println!("New files: {:#?}", plan.new_files);
+                println!("Changed files: {:#?}", plan.changed_files);
+                println!("Unchanged files: {}", plan.unchanged_files.len());
+                if !plan.deletions.is_empty() {
+                    println!("Files to delete: {:#?}", plan.deletions);
+                }
+                if *dry_run {
+                    print_dry_run_notice();
+                    return Ok(());
+                }
                 println!(
                     "WARNING: This CLI does not add any encryption on its own. \
                 The files in their current state become public as soon as they're uploaded."
                 );
                 wait_for_user_confirmation(skip_confirm)?;
+                let response = client.apply_mirror_plan(storage_account, plan).await;
+                let resp = process_shadow_api_response(response)?;
+                println!("{:#?}", resp);
+            }
+            DriveCommand::StoreFiles {
+                storage_account,
+                files,
+                encrypt,
+            } => {
+                let client = shadow_client_factory(client_signer, rpc_url, commitment, None)
+                    .with_retry_policy(retry_policy);
+                let storage_account =
+                    &resolve_storage_account(&client, &signer_pubkey, storage_account).await?;
+                println!("Store Files {} {:#?}", storage_account.to_string(), files);
+                if encrypt.is_some() {
+                    println!(
+                        "Files will be encrypted before upload. \
+                    Keep your key safe: the files cannot be decrypted without it."
+                    );
+                } else {
+                    println!(
+                        "WARNING: This CLI does not add any encryption on its own. \
+                    The files in their current state become public as soon as they're uploaded. \
+                    Use --encrypt to encrypt them first."
+                    );
+                }
+                wait_for_user_confirmation(skip_confirm)?;
+                let shadow_files = files
+                    .iter()
+                    .map(|path| match encrypt {
+                        Some(key) => encrypted_shadow_file(path, key),
+                        None => process_shadow_api_response(ShadowFile::try_from(path)),
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
                 let mut responses = Vec::new();
-                for chunk in files.chunks(5) {
+                for chunk in shadow_files.chunks(5) {
                     let response = async {
-                        let resp = client
-                            .store_files(
-                                &storage_account,
-                                chunk
-                                    .into_iter()
-                                    .map(|path: &PathBuf| shadow_file_with_basename(path))
-                                    .collect(),
-                            )
-                            .await;
-                        let resp = process_shadow_api_response(resp).unwrap();
+                        let resp = client.store_files(&storage_account, chunk.to_vec()).await;
+                        let resp = process_shadow_api_response(resp)?;
                         println!("{:#?}", resp);
+                        anyhow::Ok(())
                     };
                     responses.push(response);
                 }
-                futures::stream::iter(responses)
-                    .buffer_unordered(100)
+                let failures = futures::stream::iter(responses)
+                    .buffer_unordered(concurrency.unwrap_or(100))
+                    .filter_map(|result| async move { result.err() })
                     .collect::<Vec<_>>()
                     .await;
+                for failure in &failures {
+                    println!("Failed to store files: {}", failure);
+                }
+                if !failures.is_empty() {
+                    return Err(anyhow!(
+                        "{} of {} upload batches failed",
+                        failures.len(),
+                        shadow_files.chunks(5).len()
+                    ));
+                }
             }
         }
         Ok(())
@@ -1,2 +1,3 @@
 pub mod drive;
+#[cfg(feature = "nft")]
 pub mod nft;
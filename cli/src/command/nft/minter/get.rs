@@ -1,20 +1,23 @@
-use shadow_drive_sdk::Pubkey;
-use shadowy_super_minter::state::{file_type::AccountDeserialize, ShadowySuperMinter};
-use solana_client::rpc_client::RpcClient;
+use shadow_drive_sdk::{Pubkey, Signer};
+use solana_sdk::commitment_config::CommitmentConfig;
 
-pub(crate) async fn process(minter: &Pubkey, rpc_url: &str) -> Result<(), anyhow::Error> {
-    let client = RpcClient::new(rpc_url);
+use crate::utils::{process_shadow_api_response, shadow_client_factory};
 
-    let Ok(minter_data) = client.get_account_data(minter) else {
-        return Err(anyhow::Error::msg(format!("No account found at {minter}")))
-    };
+pub(crate) async fn process(
+    client_signer: impl Signer,
+    minter: &Pubkey,
+    rpc_url: &str,
+) -> Result<(), anyhow::Error> {
+    let client = shadow_client_factory(client_signer, rpc_url, CommitmentConfig::confirmed(), None);
 
-    let mut minter_data_cursor = minter_data.as_slice();
-    let Ok(ssm) = ShadowySuperMinter::try_deserialize(&mut minter_data_cursor) else {
-        return Err(anyhow::Error::msg(format!("Failed to deserialize ShadowySuperMinter")))
-
-    };
-    println!("{ssm:#?}");
+    let status = process_shadow_api_response(client.get_mint_status(minter).await)?;
+    println!("{status:#?}");
+    println!(
+        "Minted {} of {} ({})",
+        status.items_redeemed,
+        status.items_available,
+        if status.is_live { "live" } else { "not live" }
+    );
 
     Ok(())
 }
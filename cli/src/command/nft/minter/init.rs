@@ -12,6 +12,7 @@ use serde_json::Value;
 use serde_with::{As, DisplayFromStr};
 use shadow_drive_sdk::constants::PROGRAM_ADDRESS as SDRIVE_PROGRAM_ADDRESS;
 use shadow_drive_sdk::models::ShadowFile;
+use shadow_drive_sdk::units::Shdw;
 use shadow_drive_sdk::{Pubkey, Signer, StorageConfig};
 use shadow_nft_standard::common::collection::Collection;
 use shadow_nft_standard::common::Prefix;
@@ -23,6 +24,7 @@ use shadowy_super_minter::state::file_type::{
 };
 use shadowy_super_minter::state::get_space_for_minter;
 use shadowy_super_minter::state::uniform_mint::UniformMint;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::system_program;
 use solana_sdk::transaction::Transaction;
@@ -149,7 +151,8 @@ pub(super) async fn process(
 
     match prefix {
         Prefix::ShadowDrive { account } => {
-            let sdrive_client = shadow_client_factory(client_signer, rpc_url, None);
+            let sdrive_client =
+                shadow_client_factory(client_signer, rpc_url, CommitmentConfig::confirmed(), None);
 
             // Try to get sdrive account
             if let Ok(sdrive_account) = sdrive_client.get_storage_account(&account).await {
@@ -218,15 +221,15 @@ pub(super) async fn process(
                             // Ask for swap if under amount
                             if required_shades < user_shades {
                                 let required_ui =
-                                    ((required_shades as f64) - (user_shades as f64)) / 1e9;
-                                let user_confirms_swap = Confirm::new(&format!("Insufficient SHDW. Authorize jup.ag swap for {required_ui} SHDW?")).prompt()?;
+                                    Shdw::from_shades(required_shades.abs_diff(user_shades));
+                                let user_confirms_swap = Confirm::new(&format!("Insufficient SHDW. Authorize jup.ag swap for {required_ui}?")).prompt()?;
 
                                 if user_confirms_swap {
                                     // Show balance before swap
                                     let user_sol_balance =
                                         client.get_balance(&signer.pubkey()).await? as f64 / 1e9;
-                                    let user_shdw_balance_ui = (user_shades as f64) / 1e9;
-                                    println!("Current Balance {user_sol_balance} SOL, {user_shdw_balance_ui} SHDW");
+                                    let user_shdw_balance_ui = Shdw::from_shades(user_shades);
+                                    println!("Current Balance {user_sol_balance} SOL, {user_shdw_balance_ui}");
 
                                     // Get swap tx, sign and send.
                                     let mut tx =
@@ -242,8 +245,8 @@ pub(super) async fn process(
                                     let Ok(user_shades) = user_ui_token_amount.amount.parse::<u64>() else {
                                                     return Err(anyhow::Error::msg("Failed to parse token balance"))
                                                 };
-                                    let user_shdw_balance_ui = (user_shades as f64) / 1e9;
-                                    println!("New Balance {user_sol_balance} SOL, {user_shdw_balance_ui} SHDW");
+                                    let user_shdw_balance_ui = Shdw::from_shades(user_shades);
+                                    println!("New Balance {user_sol_balance} SOL, {user_shdw_balance_ui}");
                                 }
                             }
 
@@ -25,6 +25,7 @@ use shadowy_super_minter::state::file_type::{
 };
 use shadowy_super_minter::state::get_space_for_minter;
 use shadowy_super_minter::state::uniform_mint::UniformMint;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::system_program;
 use solana_sdk::transaction::Transaction;
@@ -280,7 +281,8 @@ pub(super) async fn process(
             let has_storage_account =
                 Confirm::new("Do you have an existing Shadow Drive account you want to use?")
                     .prompt()?;
-            let sdrive_client = shadow_client_factory(client_signer, rpc_url, None);
+            let sdrive_client =
+                shadow_client_factory(client_signer, rpc_url, CommitmentConfig::confirmed(), None);
 
             if has_storage_account {
                 // Ask user for storage account. Can be name or pubkey
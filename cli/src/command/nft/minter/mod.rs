@@ -21,7 +21,7 @@ impl MinterCommand {
         match self {
             MinterCommand::Init => init::process(signer, client_signer, rpc_url).await,
 
-            MinterCommand::Get { minter } => get::process(minter, rpc_url).await,
+            MinterCommand::Get { minter } => get::process(client_signer, minter, rpc_url).await,
         }
     }
 }
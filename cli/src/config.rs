@@ -0,0 +1,96 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use solana_sdk::commitment_config::CommitmentLevel;
+use std::path::{Path, PathBuf};
+
+/// Location of the config file, relative to the home directory.
+pub const CONFIG_FILE_PATH: &str = ".config/shadow-drive/config.toml";
+
+/// Shadow Drive specific defaults, read from `~/.config/shadow-drive/config.toml`.
+/// Every field is optional and corresponds to the global flag of the same name;
+/// a flag given on the command line always wins over the file. Values in neither
+/// fall back to the Solana CLI config file, then to the flag's documented default.
+///
+/// ```toml
+/// url = "https://api.mainnet-beta.solana.com"
+/// auth = "genesysgo"
+/// priority_fee = 1000
+/// max_retries = 3
+/// retry_base_delay = 500
+/// commitment = "finalized"
+/// concurrency = 8
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShadowDriveConfig {
+    pub url: Option<String>,
+    pub keypair: Option<String>,
+    pub auth: Option<String>,
+    pub priority_fee: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub retry_base_delay: Option<u64>,
+    pub commitment: Option<CommitmentLevel>,
+    pub concurrency: Option<usize>,
+}
+
+impl ShadowDriveConfig {
+    /// The config file path for the current user, if the home directory is known.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(CONFIG_FILE_PATH))
+    }
+
+    /// Loads the config file at `path`. A missing file is an empty config,
+    /// but a file that can't be read or parsed is an error.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .map_err(|e| anyhow!("invalid config file {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!(
+                "failed to read config file {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    /// Loads the config file at [`default_path`](Self::default_path), if any.
+    pub fn load_default() -> anyhow::Result<Self> {
+        match Self::default_path() {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_round_trips_through_toml() {
+        let config = ShadowDriveConfig {
+            url: Some("https://api.devnet.solana.com".to_string()),
+            priority_fee: Some(1000),
+            max_retries: Some(3),
+            commitment: Some(CommitmentLevel::Finalized),
+            concurrency: Some(8),
+            ..Default::default()
+        };
+        let encoded = toml::to_string(&config).unwrap();
+        assert_eq!(
+            toml::from_str::<ShadowDriveConfig>(&encoded).unwrap(),
+            config
+        );
+        assert_eq!(
+            toml::from_str::<ShadowDriveConfig>("commitment = \"processed\"\nconcurrency = 2")
+                .unwrap(),
+            ShadowDriveConfig {
+                commitment: Some(CommitmentLevel::Processed),
+                concurrency: Some(2),
+                ..Default::default()
+            }
+        );
+        assert!(toml::from_str::<ShadowDriveConfig>("priority_fees = 1").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! Client-side encryption for files uploaded with `store-files --encrypt`.
+//!
+//! Files are sealed with the SDK's [EncryptionKey] before upload, see
+//! [shadow_drive_sdk::encryption] for the format. Keys are never uploaded or saved
+//! by the CLI: keeping them safe, and not losing them, is the user's responsibility.
+//! An object cannot be recovered without the key it was encrypted with.
+use anyhow::anyhow;
+use shadow_drive_sdk::models::ShadowFile;
+use std::path::{Path, PathBuf};
+
+pub use shadow_drive_sdk::encryption::{EncryptionKey, KEY_LEN};
+
+/// Clap value parser for an [EncryptionKey]. Accepts a 64-character hex string,
+/// a base58 string, or the path of a file containing either form or the raw 32 bytes.
+pub fn encryption_key_arg(key: &str) -> anyhow::Result<EncryptionKey> {
+    let path = Path::new(key);
+    if path.is_file() {
+        let contents = std::fs::read(path)?;
+        if let Ok(bytes) = <[u8; KEY_LEN]>::try_from(contents.as_slice()) {
+            return Ok(EncryptionKey::from(bytes));
+        }
+        let contents = String::from_utf8(contents)
+            .map_err(|_| anyhow!("key file {} is not a valid key", path.display()))?;
+        return parse_key_string(contents.trim());
+    }
+    parse_key_string(key)
+}
+
+fn parse_key_string(key: &str) -> anyhow::Result<EncryptionKey> {
+    let bytes = if key.len() == 2 * KEY_LEN {
+        hex::decode(key).map_err(|e| anyhow!("invalid hex key: {}", e))?
+    } else {
+        bs58::decode(key)
+            .into_vec()
+            .map_err(|e| anyhow!("invalid base58 key: {}", e))?
+    };
+    let bytes = <[u8; KEY_LEN]>::try_from(bytes.as_slice())
+        .map_err(|_| anyhow!("expected a {}-byte key, got {} bytes", KEY_LEN, bytes.len()))?;
+    Ok(EncryptionKey::from(bytes))
+}
+
+/// A [ShadowFile] named by the path's basename, whose contents are encrypted with `key`.
+pub fn encrypted_shadow_file(path: &PathBuf, key: &EncryptionKey) -> anyhow::Result<ShadowFile> {
+    ShadowFile::builder()
+        .path(path)
+        .encrypt(key.clone())
+        .build()
+        .map_err(|e| anyhow!("failed to encrypt {}: {:?}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_then_decrypt_round_trips() {
+        let key = parse_key_string(&"ab".repeat(KEY_LEN)).unwrap();
+        let sealed = key.encrypt(b"shadow drive").unwrap();
+        assert_ne!(&sealed[..], b"shadow drive");
+        assert_eq!(key.decrypt(&sealed).unwrap(), b"shadow drive");
+    }
+
+    #[test]
+    fn decrypt_with_wrong_key_fails() {
+        let key = EncryptionKey::from([1; KEY_LEN]);
+        let sealed = key.encrypt(b"shadow drive").unwrap();
+        assert!(EncryptionKey::from([2; KEY_LEN]).decrypt(&sealed).is_err());
+    }
+
+    #[test]
+    fn malformed_keys_are_rejected() {
+        assert!(parse_key_string("zz").is_err());
+        assert!(parse_key_string(&"zz".repeat(KEY_LEN)).is_err());
+        assert!(parse_key_string(&bs58::encode([1; 16]).into_string()).is_err());
+    }
+}
@@ -1,15 +1,23 @@
 pub mod command;
+pub mod config;
+pub mod encryption;
 pub mod process;
+pub mod retries;
 
 pub mod state;
+pub mod timings;
 pub mod utils;
 
 use clap::Parser;
+use solana_sdk::commitment_config::CommitmentLevel;
 
-use command::{drive::*, nft::*};
+use command::drive::*;
+#[cfg(feature = "nft")]
+use command::nft::*;
 
 /// Manually specify a cluster url and/or keypair.
-/// Those values otherwise default to the Solana CLI config file.
+/// Those values otherwise default to `~/.config/shadow-drive/config.toml`
+/// (see [config::ShadowDriveConfig]), then to the Solana CLI config file.
 /// All values other than `url` and `keypair` exist only to satisfy compatibility
 /// with keypair resolution.
 #[derive(Debug, Parser)]
@@ -41,6 +49,37 @@ pub struct ConfigOverride {
     /// acquiring an auth token.
     #[clap(long)]
     pub auth: Option<String>,
+    /// Print how long each phase of the command took (auth, RPC
+    /// account fetch, transaction confirmation, upload).
+    #[clap(long)]
+    pub timings: bool,
+    /// After a command sends a transaction, wait until it reaches finalized
+    /// commitment before reporting success, so that a following command sees its effects.
+    #[clap(long)]
+    pub wait_finalized: bool,
+    /// Priority fee, in micro-lamports per compute unit, added to every
+    /// transaction sent by drive commands. Helps transactions land when the
+    /// network is congested. To pick a value, look at the fees recently paid
+    /// by similar transactions via the `getRecentPrioritizationFees` RPC method.
+    /// Defaults to 0.
+    #[clap(long, value_name = "MICRO_LAMPORTS")]
+    pub priority_fee: Option<u64>,
+    /// How many times to retry an upload or storage account fetch that fails
+    /// with a connection error, a timeout, or a 429/5xx response. Defaults to 0.
+    #[clap(long)]
+    pub max_retries: Option<u32>,
+    /// Delay in milliseconds before the first retry. Doubles after each retry.
+    /// Defaults to 500.
+    #[clap(long, value_name = "MILLISECONDS")]
+    pub retry_base_delay: Option<u64>,
+    /// Commitment level of RPC requests made by drive commands: `processed`,
+    /// `confirmed` or `finalized`. Defaults to `confirmed`.
+    #[clap(long, value_name = "LEVEL")]
+    pub commitment: Option<CommitmentLevel>,
+    /// Maximum number of requests a command runs at once, such as upload
+    /// batches or cranks. Defaults to 100 for uploads and 4 for cranks.
+    #[clap(long)]
+    pub concurrency: Option<usize>,
 }
 
 /// Perform Shadow Drive operations on the command-line.
@@ -57,11 +96,14 @@ pub struct Opts {
 
 #[derive(Debug, Parser)]
 pub enum Command {
-    /// Commands for creating and managing shadow drive accounts and files
+    /// Commands for creating and managing shadow drive accounts and files.
+    /// Storage accounts can be given by address or by identifier, which
+    /// is looked up among the signer's storage accounts.
     #[clap(subcommand, name = "drive")]
     DriveCommand(DriveCommand),
 
     /// Commands for creating and managing shadow nft minters and metadata accounts
+    #[cfg(feature = "nft")]
     #[clap(subcommand, name = "nft")]
     NftCommand(NftCommand),
 }
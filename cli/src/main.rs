@@ -1,17 +1,33 @@
 use anyhow::anyhow;
 use clap::{IntoApp, Parser};
+use shadow_drive_cli::config::ShadowDriveConfig;
+use shadow_drive_cli::retries::RetryCounter;
+use shadow_drive_cli::timings::PhaseTimings;
 use shadow_drive_cli::Opts;
+use shadow_drive_sdk::RetryPolicy;
 use shadow_rpc_auth::{authenticate, parse_account_id_from_url};
 use solana_clap_v3_utils::keypair::keypair_from_path;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use std::time::{Duration, Instant};
+use tracing_subscriber::prelude::*;
 
 pub const GENESYSGO_AUTH_KEYWORD: &str = "genesysgo";
 
 const NO_CONFIG_FILE: &str = "\
 Cannot find a config file. You likely do not have the official Solana CLI installed.
-Either install the Solana CLI or place a configuration file at ~/.config/solana/cli/config.yml
+Either install the Solana CLI, place a configuration file at ~/.config/solana/cli/config.yml,
+or pass both --keypair and --url.
 See https://docs.solana.com/cli/install-solana-cli-tools for installation details.
 ";
 
+/// Loads the Solana CLI config file, failing with [NO_CONFIG_FILE] if there is none.
+fn load_solana_config() -> anyhow::Result<solana_cli_config::Config> {
+    let config_file = solana_cli_config::CONFIG_FILE
+        .as_ref()
+        .ok_or_else(|| anyhow!("unable to determine a config file path on this OS or user"))?;
+    solana_cli_config::Config::load(config_file).map_err(|_| anyhow!(NO_CONFIG_FILE))
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // CLI Parse
@@ -20,16 +36,23 @@ async fn main() -> anyhow::Result<()> {
     // Get signer string from either an argument or the Solana CLI config file
     let app = Opts::into_app();
     let matches = app.get_matches();
-    let config = {
-        let config_file = solana_cli_config::CONFIG_FILE
-            .as_ref()
-            .ok_or_else(|| anyhow!("unable to determine a config file path on this OS or user"))?;
-        solana_cli_config::Config::load(&config_file).map_err(|_| anyhow!(NO_CONFIG_FILE))
-    }?;
-    let keypath = opts
-        .cfg_override
-        .keypair
-        .unwrap_or_else(|| config.keypair_path.clone());
+    // Shadow Drive defaults, which command-line flags override
+    let drive_config = ShadowDriveConfig::load_default()?;
+    let keypath = opts.cfg_override.keypair.or(drive_config.keypair);
+    // Resolve the RPC URL from a command-line arg or the Shadow Drive config file.
+    let url = opts.cfg_override.url.or(drive_config.url);
+    // Only fall back to the Solana CLI config file for values given neither way,
+    // so the Solana CLI doesn't need to be installed when both are.
+    let (keypath, url) = match (keypath, url) {
+        (Some(keypath), Some(url)) => (keypath, url),
+        (keypath, url) => {
+            let config = load_solana_config()?;
+            (
+                keypath.unwrap_or(config.keypair_path),
+                url.unwrap_or(config.json_rpc_url),
+            )
+        }
+    };
     let signer = keypair_from_path(
         &matches,
         shellexpand::tilde(&keypath).as_ref(),
@@ -46,25 +69,67 @@ async fn main() -> anyhow::Result<()> {
     )
     .unwrap();
 
-    // Resolve the RPC URL from either a command-line arg or the Solana CLI config file.
-    let url = opts.cfg_override.url.unwrap_or(config.json_rpc_url);
+    // Time each phase of the command if requested, and count retried requests
+    let timings = PhaseTimings::default();
+    let retries = RetryCounter::default();
+    tracing_subscriber::registry()
+        .with(opts.cfg_override.timings.then(|| timings.clone()))
+        .with(retries.clone())
+        .init();
 
     // Possibly perform a sign-in operation
-    let mut auth: Option<String> = opts.cfg_override.auth.clone();
-    if opts.cfg_override.auth == Some(GENESYSGO_AUTH_KEYWORD.to_string()) {
+    let mut auth: Option<String> = opts.cfg_override.auth.or(drive_config.auth);
+    if auth.as_deref() == Some(GENESYSGO_AUTH_KEYWORD) {
+        let start = Instant::now();
         let account_id = parse_account_id_from_url(url.to_string())?;
         let token = authenticate(&signer, &account_id).await?;
+        timings.record("auth", start.elapsed());
         auth = Some(token)
     };
 
-    opts.command
+    let start = Instant::now();
+    let result = opts
+        .command
         .process(
             &signer,
             signer_2,
             &url,
             opts.cfg_override.skip_confirm,
+            opts.cfg_override.wait_finalized,
+            opts.cfg_override
+                .priority_fee
+                .or(drive_config.priority_fee)
+                .unwrap_or(0),
+            RetryPolicy {
+                max_retries: opts
+                    .cfg_override
+                    .max_retries
+                    .or(drive_config.max_retries)
+                    .unwrap_or(0),
+                base_delay: Duration::from_millis(
+                    opts.cfg_override
+                        .retry_base_delay
+                        .or(drive_config.retry_base_delay)
+                        .unwrap_or(500),
+                ),
+            },
+            CommitmentConfig {
+                commitment: opts
+                    .cfg_override
+                    .commitment
+                    .or(drive_config.commitment)
+                    .unwrap_or(CommitmentLevel::Confirmed),
+            },
+            opts.cfg_override.concurrency.or(drive_config.concurrency),
             auth,
         )
-        .await?;
-    Ok(())
+        .await;
+    if retries.count() > 0 {
+        eprintln!("Retried {} failed requests", retries.count());
+    }
+    if opts.cfg_override.timings {
+        timings.record("total", start.elapsed());
+        timings.print();
+    }
+    result
 }
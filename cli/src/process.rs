@@ -1,4 +1,6 @@
 use super::Command;
+use shadow_drive_sdk::RetryPolicy;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::signature::Signer;
 
 impl Command {
@@ -8,16 +10,33 @@ impl Command {
         client_signer: T,
         rpc_url: &str,
         skip_confirm: bool,
+        wait_finalized: bool,
+        priority_fee: u64,
+        retry_policy: RetryPolicy,
+        commitment: CommitmentConfig,
+        concurrency: Option<usize>,
         auth: Option<String>,
     ) -> anyhow::Result<()> {
         println!();
         match self {
             Command::DriveCommand(drive_command) => {
                 drive_command
-                    .process(signer, client_signer, rpc_url, skip_confirm, auth)
+                    .process(
+                        signer,
+                        client_signer,
+                        rpc_url,
+                        skip_confirm,
+                        wait_finalized,
+                        priority_fee,
+                        retry_policy,
+                        commitment,
+                        concurrency,
+                        auth,
+                    )
                     .await
             }
 
+            #[cfg(feature = "nft")]
             Command::NftCommand(nft_command) => {
                 nft_command.process(signer, client_signer, rpc_url).await
             }
@@ -0,0 +1,27 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use tracing::{Event, Subscriber};
+use tracing_subscriber::{layer::Context, Layer};
+
+/// The SDK logs each retried request under this target.
+const SDK_RETRY_TARGET: &str = "shadow_drive_sdk::client::retry";
+
+/// A [Layer] that counts the requests retried by the SDK's retry policy.
+#[derive(Clone, Default)]
+pub struct RetryCounter {
+    retries: Arc<AtomicU32>,
+}
+
+impl RetryCounter {
+    pub fn count(&self) -> u32 {
+        self.retries.load(Ordering::Relaxed)
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RetryCounter {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() == SDK_RETRY_TARGET {
+            self.retries.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
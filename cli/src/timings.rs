@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{span, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+/// Only spans emitted by the SDK are timed.
+const SDK_TARGET: &str = "shadow_drive_sdk";
+
+/// A [Layer] that accumulates the wall-clock time spent in each phase
+/// of a command, keyed by span name (e.g. "rpc_account_fetch", "upload").
+#[derive(Clone, Default)]
+pub struct PhaseTimings {
+    phases: Arc<Mutex<BTreeMap<String, (u32, Duration)>>>,
+}
+
+struct SpanStart(Instant);
+
+impl PhaseTimings {
+    /// Add a duration to a phase, for work that happens outside of SDK spans.
+    pub fn record(&self, phase: &str, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap();
+        let (count, total) = phases.entry(phase.to_string()).or_default();
+        *count += 1;
+        *total += elapsed;
+    }
+
+    pub fn print(&self) {
+        println!("Timings:");
+        for (phase, (count, total)) in self.phases.lock().unwrap().iter() {
+            println!("  {}: {:?} ({} calls)", phase, total, count);
+        }
+    }
+}
+
+impl<S> Layer<S> for PhaseTimings
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if !attrs.metadata().target().starts_with(SDK_TARGET) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(SpanStart(start)) = span.extensions().get::<SpanStart>() {
+                self.record(span.name(), start.elapsed());
+            }
+        }
+    }
+}
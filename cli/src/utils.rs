@@ -1,29 +1,117 @@
 use anyhow::anyhow;
 use byte_unit::Byte;
-use chrono::DateTime;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use reqwest::Response;
+use itertools::Itertools;
 use shadow_drive_sdk::constants::SHDW_DRIVE_OBJECT_PREFIX;
 use shadow_drive_sdk::error::{Error, FileError};
-use shadow_drive_sdk::models::{ShadowDriveResult, ShadowFile};
-use shadow_drive_sdk::ShadowDriveClient;
-use shadow_rpc_auth::HttpSenderWithHeaders;
+use shadow_drive_sdk::models::ShadowDriveResult;
+use shadow_drive_sdk::units::parse_storage_size;
+use shadow_drive_sdk::{
+    ConfirmationConfig, OperationKind, ShadowDriveClient, StorageAccountFilter,
+};
+use shadow_rpc_auth::{bearer_headers, HttpSenderWithHeaders};
 use solana_client::nonblocking;
-use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::lamports_to_sol;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Signature, Signer, SignerError};
 use std::io::stdin;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 /// Maximum amount of files to batch into a single [store_files] request.
 pub const FILE_UPLOAD_BATCH_SIZE: usize = 5;
 
+/// Timeout of the Solana RPC clients built by [shadow_client_factory], matching
+/// [ShadowDriveClient::new].
+const RPC_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How a command prints its result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum OutputFormat {
+    /// Human-readable lines.
+    Text,
+    /// A single JSON object.
+    Json,
+    /// One JSON object per line, printed as results arrive.
+    #[clap(name = "jsonl")]
+    JsonLines,
+}
+
 /// Clap value parser for base58 string representations of [Pubkey].
 pub fn pubkey_arg(pubkey: &str) -> anyhow::Result<Pubkey> {
     Pubkey::from_str(pubkey).map_err(|e| anyhow!("invalid pubkey: {}", e.to_string()))
 }
 
+/// A storage account named on the command line, either by address or by identifier.
+#[derive(Debug, Clone)]
+pub enum StorageAccountArg {
+    Pubkey(Pubkey),
+    Identifier(String),
+}
+
+impl std::fmt::Display for StorageAccountArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageAccountArg::Pubkey(pubkey) => write!(f, "{}", pubkey),
+            StorageAccountArg::Identifier(identifier) => write!(f, "{}", identifier),
+        }
+    }
+}
+
+/// Clap value parser for a [StorageAccountArg]. Anything that is not a
+/// valid base58 [Pubkey] is treated as an identifier.
+pub fn storage_account_arg(arg: &str) -> anyhow::Result<StorageAccountArg> {
+    Ok(match Pubkey::from_str(arg) {
+        Ok(pubkey) => StorageAccountArg::Pubkey(pubkey),
+        Err(_) => StorageAccountArg::Identifier(arg.to_string()),
+    })
+}
+
+/// Resolves a [StorageAccountArg] to the account's address, looking identifiers
+/// up among the accounts of `owner`. Fails if no account, or more than one, has the identifier.
+pub async fn resolve_storage_account<T: Signer>(
+    client: &ShadowDriveClient<T>,
+    owner: &Pubkey,
+    arg: &StorageAccountArg,
+) -> anyhow::Result<Pubkey> {
+    let identifier = match arg {
+        StorageAccountArg::Pubkey(pubkey) => return Ok(*pubkey),
+        StorageAccountArg::Identifier(identifier) => identifier,
+    };
+    let accounts = client
+        .get_storage_accounts(owner, StorageAccountFilter::All)
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "failed to look up storage account {:?}: {:?}",
+                identifier,
+                e
+            )
+        })?;
+    let matches = accounts
+        .iter()
+        .filter(|account| account.identifier() == identifier)
+        .map(|account| account.storage_account())
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [] => Err(anyhow!(
+            "{:?} is neither a valid pubkey nor the identifier of a storage account owned by {}",
+            identifier,
+            owner
+        )),
+        [pubkey] => Ok(*pubkey),
+        pubkeys => Err(anyhow!(
+            "{} storage accounts owned by {} are named {:?}, pass one of their addresses instead: {}",
+            pubkeys.len(),
+            owner,
+            identifier,
+            pubkeys.iter().join(", ")
+        )),
+    }
+}
+
 /// To get around using a [Box<dyn Signer>] with [ShadowDriveClient].
 ///
 /// TODO: cleanup if not necessary
@@ -49,41 +137,28 @@ impl Signer for WrappedSigner {
     }
 }
 
-/// Further diagnostic printing wherever possible.
+/// Converts an SDK result into an [anyhow::Result] with a readable description
+/// of the error, for the caller to propagate with `?`. Never prints or panics;
+/// errors are reported once, by whoever handles them.
 pub fn process_shadow_api_response<T>(response: ShadowDriveResult<T>) -> anyhow::Result<T> {
-    match response {
-        Ok(response) => Ok(response),
-        Err(err) => match err {
-            Error::ShadowDriveServerError { status, message } => {
-                let err = format!(
-                    "Shadow Drive Server Error {}: {:#?}",
-                    status,
-                    message.to_string()
-                );
-                println!("{}", err);
-                Err(anyhow!("{}", err))
-            }
-            Error::FileSystemError(err) => {
-                let err = format!("Filesystem Error: {:#?}", err.to_string());
-                println!("{}", err);
-                Err(anyhow!("{}", err))
-            }
-            Error::FileValidationError(errs) => {
-                let mut err_vec = vec![];
-                for err in errs {
-                    let FileError { file, error } = err;
-                    let err = format!("File Validation Error for {}: {}", file, error);
-                    err_vec.push(err);
-                }
-                println!("{:#?}", err_vec);
-                Err(anyhow!("{:#?}", err_vec))
-            }
-            e => {
-                println!("{:#?}", e);
-                Err(anyhow!("{:#?}", e))
-            }
-        },
-    }
+    response.map_err(|err| match err {
+        Error::ShadowDriveServerError { status, message } => anyhow!(
+            "Shadow Drive Server Error {}: {:#?}",
+            status,
+            message.to_string()
+        ),
+        Error::FileSystemError(err) => anyhow!("Filesystem Error: {:#?}", err.to_string()),
+        Error::FileValidationError(errs) => {
+            let err_vec = errs
+                .into_iter()
+                .map(|FileError { file, error }| {
+                    format!("File Validation Error for {}: {}", file, error)
+                })
+                .collect::<Vec<_>>();
+            anyhow!("{:#?}", err_vec)
+        }
+        e => anyhow!("{:#?}", e),
+    })
 }
 
 /// Generate a Shadow Drive file URL from storage account and filename.
@@ -96,82 +171,43 @@ pub fn storage_object_url(storage_account: &Pubkey, file: &str) -> String {
     )
 }
 
-/// Returns false when "Content-Type" header is not "text/plain".
-fn is_text_response(headers: &HeaderMap) -> anyhow::Result<bool> {
-    let content_type = headers
-        .get("content-type")
-        .and_then(|s| Some(s.to_str()))
-        .transpose()?;
-    Ok(content_type == Some("text/plain"))
+/// Convert a file size string to [Byte] object with the denoted size.
+/// KB, MB, GB are binary multiples (KiB, MiB, GiB), matching on-chain storage pricing.
+pub fn parse_filesize(size: &str) -> anyhow::Result<Byte> {
+    parse_storage_size(size).map_err(|_| {
+        anyhow!(
+            "invalid filesize, \
+        expected a number followed by KB, MB, GB (binary, e.g. 1MB = 1024KB): {}",
+            size
+        )
+    })
 }
 
-/// Check with a HEAD that the URL exists and is a "text/plain" file.
-/// If so, return the response of a GET request.
-pub async fn get_text(url: &String) -> anyhow::Result<Response> {
-    let http_client = reqwest::Client::new();
-    let head_resp = http_client.head(url).send().await?;
-    if !is_text_response(head_resp.headers())? {
-        return Err(anyhow!("Not a text file at url {}", url));
-    }
-    Ok(http_client.get(url).send().await?)
-}
-
-#[derive(Debug)]
-pub struct FileMetadata {
-    pub timestamp: i64,
-    pub content_type: String,
-    pub last_modified: i64,
-    pub etag: String,
-    pub storage_account: String,
-    pub storage_owner: String,
-}
-
-impl FileMetadata {
-    pub fn from_headers(h: &HeaderMap) -> anyhow::Result<Self> {
-        let getter = |key| {
-            Ok::<_, anyhow::Error>(
-                h.get(key)
-                    .ok_or(anyhow!("Missing file metadata header: {}", key))?
-                    .to_str()?
-                    .to_string(),
-            )
-        };
-        let parse_timestamp = |key| {
-            let timestamp = getter(key)?;
-            let timestamp = DateTime::parse_from_rfc2822(&timestamp)?;
-            Ok::<_, anyhow::Error>(timestamp.timestamp())
-        };
-        let timestamp = parse_timestamp("date")?;
-        let last_modified = parse_timestamp("last-modified")?;
-        Ok(Self {
-            timestamp,
-            content_type: getter("content-type")?,
-            last_modified,
-            etag: getter("etag")?,
-            storage_account: getter("x-amz-meta-owner-account-pubkey")?,
-            storage_owner: getter("x-amz-meta-storage-account-pubkey")?,
-        })
+/// Recursively collect the paths of all files under `dir`.
+pub fn files_in_directory(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_in_directory(&path)?);
+        } else {
+            files.push(path);
+        }
     }
+    Ok(files)
 }
 
-/// Pulls "last-modified" from [HeaderMap], unaltered.
-pub fn last_modified(headers: &HeaderMap) -> anyhow::Result<String> {
-    Ok(headers
-        .get("last-modified")
-        .ok_or(anyhow!("'last modified' header not found"))?
-        .to_str()?
-        .to_string())
+/// Total size in bytes of all files under `dir`, recursively.
+pub fn directory_size(dir: &Path) -> anyhow::Result<u64> {
+    files_in_directory(dir)?
+        .iter()
+        .map(|file| Ok(file.metadata()?.len()))
+        .sum()
 }
 
-/// Convert a file size string to [Byte] object with the denoted size.
-pub fn parse_filesize(size: &str) -> anyhow::Result<Byte> {
-    Byte::from_str(size).map_err(|e| {
-        anyhow!(
-            "invalid filesize, \
-        expected a number followed by KB, MB, GB:\n{}",
-            e.to_string()
-        )
-    })
+/// Ends a `--dry-run` after the changes it would make have been printed.
+pub fn print_dry_run_notice() {
+    println!("Dry run: no transaction or request was sent, nothing was changed.");
 }
 
 /// Confirm from the user that they definitely want some irreversible
@@ -186,27 +222,61 @@ pub fn wait_for_user_confirmation(skip: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Print the estimated network fee of `op` and, if the operation pays for
+/// `size` of storage, its SHDW cost. Estimation failures are reported but
+/// don't prevent the operation.
+pub async fn print_estimated_cost<T: Signer>(
+    client: &ShadowDriveClient<T>,
+    op: OperationKind,
+    size: Option<&Byte>,
+) {
+    match client.estimate_transaction_fee(op).await {
+        Ok(lamports) => println!("Estimated network fee: {} SOL", lamports_to_sol(lamports)),
+        Err(e) => println!("Failed to estimate network fee: {:?}", e),
+    }
+    if let Some(size) = size {
+        match client.estimate_storage_cost(size.clone()).await {
+            Ok(cost) => println!("Estimated storage cost: {}", cost),
+            Err(e) => println!("Failed to estimate storage cost: {:?}", e),
+        }
+    }
+}
+
+/// If `wait` is set, block until the transaction `txid` is finalized,
+/// so that a following command observes its effects.
+pub async fn wait_for_finalization<T: Signer>(
+    client: &ShadowDriveClient<T>,
+    txid: &str,
+    wait: bool,
+) -> anyhow::Result<()> {
+    if !wait {
+        return Ok(());
+    }
+    println!("Waiting for transaction {} to be finalized...", txid);
+    let response = client
+        .wait_for_confirmation(txid, &ConfirmationConfig::finalized())
+        .await;
+    process_shadow_api_response(response)
+}
+
 /// We either create an authenticated client with default auth headers,
-/// or else we simply use the [RpcClient] provided by the normal
-/// [ShadowDriveClient] constructor.
+/// or else a plain [RpcClient] like the normal [ShadowDriveClient] constructor's.
+/// Either way its RPC requests use `commitment`.
 pub fn shadow_client_factory<T: Signer>(
     signer: T,
     url: &str,
+    commitment: CommitmentConfig,
     auth: Option<String>,
 ) -> ShadowDriveClient<T> {
     if let Some(auth) = auth {
-        let mut headers = HeaderMap::new();
-        headers.append(
-            HeaderName::from_str("Authorization").unwrap(),
-            HeaderValue::from_str(&format!("Bearer {}", auth)).unwrap(),
-        );
+        let headers = bearer_headers(&auth).expect("invalid auth token");
         let rpc_client = nonblocking::rpc_client::RpcClient::new_sender(
             HttpSenderWithHeaders::new(url, Some(headers.clone())),
-            Default::default(),
+            RpcClientConfig::with_commitment(commitment),
         );
         let client = RpcClient::new_sender(
             HttpSenderWithHeaders::new(url, Some(headers)),
-            Default::default(),
+            RpcClientConfig::with_commitment(commitment),
         );
         let balance = client.get_balance(&signer.pubkey());
         match balance {
@@ -219,19 +289,11 @@ pub fn shadow_client_factory<T: Signer>(
         }
         ShadowDriveClient::new_with_rpc(signer, rpc_client)
     } else {
-        ShadowDriveClient::new(signer, url)
+        let rpc_client = nonblocking::rpc_client::RpcClient::new_with_timeout_and_commitment(
+            url.to_string(),
+            RPC_TIMEOUT,
+            commitment,
+        );
+        ShadowDriveClient::new_with_rpc(signer, rpc_client)
     }
 }
-
-// TODO Maybe make this a result type.
-/// Factory function for a [ShadowFile], where we just use the path's
-/// basename. Panics if `path.file_name()` returns None.
-pub fn shadow_file_with_basename(path: &PathBuf) -> ShadowFile {
-    let basename = {
-        path.file_name()
-            .and_then(|s| s.to_str())
-            .unwrap()
-            .to_string()
-    };
-    ShadowFile::file(basename, path.clone())
-}
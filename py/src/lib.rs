@@ -2,21 +2,26 @@ use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use futures::{stream, StreamExt};
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::{
     pymodule,
-    types::{PyModule, PyString},
+    types::{PyBytes, PyDict, PyModule, PyString},
     PyResult, Python,
 };
+use reqwest::header::{CONTENT_TYPE, LAST_MODIFIED};
 use shadow_drive_sdk::constants::SHDW_DRIVE_OBJECT_PREFIX;
-use shadow_drive_sdk::models::{ShadowFile, ShadowUploadResponse};
+use shadow_drive_sdk::models::{storage_acct::StorageAcct, ShadowFile, ShadowUploadResponse};
 use shadow_drive_sdk::{
     read_keypair_file, Byte, CommitmentConfig, Keypair, Pubkey, RpcClient,
     ShadowDriveClient as ShadowDriveRustClient, Signer,
 };
 use tokio::runtime::{Builder, Runtime};
 
+/// Number of upload requests upload_files sends at once, unless told otherwise.
+const DEFAULT_UPLOAD_CONCURRENCY: usize = 5;
+
 /// A Python module implemented in Rust.
 #[pymodule]
 fn shadow_drive(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -328,12 +333,23 @@ fn shadow_drive(_py: Python, m: &PyModule) -> PyResult<()> {
             })
         }
 
-        /// upload_files(files, /)
+        /// upload_files(files, progress, batch_size, concurrency, /)
         /// --
         ///
         /// Upload the specified files. Note that any non-Unicode characters in the file name are
-        /// converted to the U+FFFD REPLACEMENT CHARACTER.
-        fn upload_files(&self, files: Vec<&str>, py: Python) -> PyResult<Vec<Py<PyString>>> {
+        /// converted to the U+FFFD REPLACEMENT CHARACTER. Files are sent in upload requests of
+        /// batch_size files, up to concurrency of them at a time (5 by default). By default all
+        /// files go in one request, or one request per file if a progress callable is provided.
+        /// progress(files_completed, files_total) is called as each request finishes, whether or
+        /// not its files were uploaded. Returns the urls of the uploaded files.
+        fn upload_files(
+            &self,
+            files: Vec<&str>,
+            progress: Option<PyObject>,
+            batch_size: Option<usize>,
+            concurrency: Option<usize>,
+            py: Python,
+        ) -> PyResult<Vec<Py<PyString>>> {
             if let Some(ref storage_account) = self.current_account {
                 // Turn files provided into ShadowFiles
                 let files: Vec<ShadowFile> = files
@@ -354,31 +370,97 @@ fn shadow_drive(_py: Python, m: &PyModule) -> PyResult<()> {
                     })
                     .collect::<PyResult<Vec<ShadowFile>>>()?;
 
-                // Upload files
-                let response: ShadowUploadResponse = self
-                    .runtime
-                    .block_on(self.rust_client.store_files(storage_account, files))
-                    .map_err(|err| {
-                        PyValueError::new_err(format!("failed to upload files: {err:?}"))
-                    })?;
+                let default_batch_size = if progress.is_some() { 1 } else { files.len() };
+                let batch_size = batch_size.unwrap_or(default_batch_size).max(1);
+                let concurrency = concurrency.unwrap_or(DEFAULT_UPLOAD_CONCURRENCY).max(1);
+                let total = files.len();
 
-                // Alert the user of any errors
-                for error in &response.upload_errors {
-                    println!("failed to upload file {}: {}", &error.file, &error.error);
-                }
+                // Upload files
+                self.runtime.block_on(async {
+                    let mut uploads = stream::iter(files.chunks(batch_size).map(<[_]>::to_vec))
+                        .map(|batch| async move {
+                            let len = batch.len();
+                            let response =
+                                self.rust_client.store_files(storage_account, batch).await;
+                            (len, response)
+                        })
+                        .buffer_unordered(concurrency);
+
+                    let mut successes = vec![];
+                    let mut failures = vec![];
+                    let mut completed = 0;
+                    while let Some((len, response)) = uploads.next().await {
+                        completed += len;
+                        match response {
+                            Ok(response) => {
+                                // Alert the user of any errors
+                                for error in &response.upload_errors {
+                                    println!(
+                                        "failed to upload file {}: {}",
+                                        &error.file, &error.error
+                                    );
+                                }
+                                successes.extend(
+                                    response
+                                        .finalized_locations
+                                        .iter()
+                                        .map(|loc| PyString::new(py, loc).into()),
+                                );
+                            }
+                            Err(err) => {
+                                println!("failed to upload {len} files: {err:?}");
+                                failures.push(err);
+                            }
+                        }
+                        if let Some(ref progress) = progress {
+                            progress.call1(py, (completed, total))?;
+                        }
+                    }
 
-                // Return successful uploads
-                let successes = response
-                    .finalized_locations
-                    .iter()
-                    .map(|loc| PyString::new(py, loc).into())
-                    .collect();
-                Ok(successes)
+                    // Only fail outright if no request went through
+                    match failures.into_iter().next() {
+                        Some(err) if successes.is_empty() => Err(PyValueError::new_err(format!(
+                            "failed to upload files: {err:?}"
+                        ))),
+                        _ => Ok(successes),
+                    }
+                })
             } else {
                 Err(PyRuntimeError::new_err("No storage account is specified. Create one with create_account, or specify one with set_account"))
             }
         }
 
+        /// upload_bytes(name, data, /)
+        /// --
+        ///
+        /// Upload in-memory data to the current storage account under the specified name, without
+        /// writing it to a file first. Returns the finalized url of the file.
+        fn upload_bytes(&self, name: &str, data: &[u8]) -> PyResult<String> {
+            let storage_account = self.current_account.ok_or_else(|| {
+                PyRuntimeError::new_err("No storage account is specified. Create one with create_account, or specify one with set_account")
+            })?;
+            let file = ShadowFile::bytes(name.to_string(), data.to_vec());
+
+            let response: ShadowUploadResponse = self
+                .runtime
+                .block_on(self.rust_client.store_files(&storage_account, vec![file]))
+                .map_err(|err| {
+                    PyValueError::new_err(format!("failed to upload {name}: {err:?}"))
+                })?;
+
+            if let Some(error) = response.upload_errors.first() {
+                return Err(PyValueError::new_err(format!(
+                    "failed to upload {}: {}",
+                    error.file, error.error
+                )));
+            }
+            response
+                .finalized_locations
+                .into_iter()
+                .next()
+                .ok_or_else(|| PyRuntimeError::new_err(format!("no url was returned for {name}")))
+        }
+
         /// delete_files(file_urls, /)
         /// --
         ///
@@ -432,29 +514,27 @@ fn shadow_drive(_py: Python, m: &PyModule) -> PyResult<()> {
             }
         }
 
-        /// get_file(/)
+        /// get_file(file, /)
         /// --
         ///
         /// Retrieve the specified file if it exists in the storage account. Can also provide a url to a file (need not be in the current storage account).
         fn get_file(&self, file: &str) -> PyResult<Vec<u8>> {
-            let url = if file.contains(SHDW_DRIVE_OBJECT_PREFIX) {
-                file.to_string()
-            } else {
-                if let Some(ref storage_account) = self.current_account {
-                    format!("{SHDW_DRIVE_OBJECT_PREFIX}")
-                } else {
-                    return Err(PyRuntimeError::new_err("No storage account is specified. Create one with create_account, specify one with set_account, or pass in the 'account' optional arugment"));
-                }
-            };
-            self.runtime.block_on(async move {
-                reqwest::get(url)
-                    .await
-                    .map(|response| response.bytes())
-                    .map_err(|e| PyRuntimeError::new_err(format!("failed to retrieve file {e:?}")))?
-                    .await
-                    .map(|bytes| bytes.to_vec())
-                    .map_err(|e| PyRuntimeError::new_err(format!("failed to retrieve file {e:?}")))
-            })
+            self.fetch_file(file).map(|(data, _, _)| data)
+        }
+
+        /// get_file_with_metadata(file, /)
+        /// --
+        ///
+        /// Like get_file, but returns a (data, content_type, last_modified) tuple. data is bytes,
+        /// content_type is the file's MIME type, and last_modified is the HTTP date the file was
+        /// last written. The latter two are None if the server did not report them.
+        fn get_file_with_metadata(
+            &self,
+            file: &str,
+            py: Python,
+        ) -> PyResult<(Py<PyBytes>, Option<String>, Option<String>)> {
+            let (data, content_type, last_modified) = self.fetch_file(file)?;
+            Ok((PyBytes::new(py, &data).into(), content_type, last_modified))
         }
 
         /// cancel_delete_storage(/)
@@ -552,6 +632,58 @@ fn shadow_drive(_py: Python, m: &PyModule) -> PyResult<()> {
             }
         }
 
+        /// get_account(account, /)
+        /// --
+        ///
+        /// Fetch the state of the provided storage account, or of the current storage account if
+        /// account=None, as a dict with the keys storage_account, identifier, version, storage,
+        /// current_usage, immutable, to_be_deleted, delete_request_epoch, owner_1, owner_2
+        /// (None for v2 accounts), creation_time, creation_epoch and last_fee_epoch.
+        fn get_account(&self, account: Option<&str>, py: Python) -> PyResult<Py<PyDict>> {
+            let storage_account = account
+                .map(try_pubkey)
+                .transpose()?
+                .or(self.current_account)
+                .ok_or_else(|| PyRuntimeError::new_err("No storage account is specified. Create one with create_account, specify one with set_account, or pass in the 'account' optional arugment"))?;
+
+            let info = self
+                .runtime
+                .block_on(self.rust_client.get_storage_account(&storage_account))
+                .map_err(|e| {
+                    PyRuntimeError::new_err(format!("unable to retrieve storage account {e:?}"))
+                })?;
+
+            let dict = PyDict::new(py);
+            dict.set_item("storage_account", info.storage_account().to_string())?;
+            dict.set_item("identifier", info.identifier())?;
+            dict.set_item("version", info.version().to_string())?;
+            dict.set_item("storage", info.storage())?;
+            dict.set_item("immutable", info.immutable())?;
+            dict.set_item("to_be_deleted", info.to_be_deleted())?;
+            dict.set_item("owner_1", info.owner_1().to_string())?;
+            dict.set_item("creation_time", info.creation_time())?;
+            match info.as_versioned() {
+                StorageAcct::V1(account) => {
+                    dict.set_item("current_usage", account.current_usage)?;
+                    dict.set_item("delete_request_epoch", account.delete_request_epoch)?;
+                    let owner_2 = Some(account.owner_2)
+                        .filter(|owner_2| *owner_2 != Pubkey::default())
+                        .map(|owner_2| owner_2.to_string());
+                    dict.set_item("owner_2", owner_2)?;
+                    dict.set_item("creation_epoch", account.creation_epoch)?;
+                    dict.set_item("last_fee_epoch", account.last_fee_epoch)?;
+                }
+                StorageAcct::V2(account) => {
+                    dict.set_item("current_usage", account.current_usage)?;
+                    dict.set_item("delete_request_epoch", account.delete_request_epoch)?;
+                    dict.set_item("owner_2", py.None())?;
+                    dict.set_item("creation_epoch", account.creation_epoch)?;
+                    dict.set_item("last_fee_epoch", account.last_fee_epoch)?;
+                }
+            }
+            Ok(dict.into())
+        }
+
         /// set_account(account, /)
         /// --
         ///
@@ -568,6 +700,44 @@ fn shadow_drive(_py: Python, m: &PyModule) -> PyResult<()> {
         }
     }
 
+    impl ShadowDriveClient {
+        /// Downloads a file by name from the current storage account, or by url, along with
+        /// its content type and last modified date.
+        fn fetch_file(&self, file: &str) -> PyResult<(Vec<u8>, Option<String>, Option<String>)> {
+            let url = if file.contains(SHDW_DRIVE_OBJECT_PREFIX) {
+                file.to_string()
+            } else if let Some(ref storage_account) = self.current_account {
+                format!("{SHDW_DRIVE_OBJECT_PREFIX}/{storage_account}/{file}")
+            } else {
+                return Err(PyRuntimeError::new_err("No storage account is specified. Create one with create_account, specify one with set_account, or pass in the url of the file"));
+            };
+            self.runtime.block_on(async move {
+                let response = reqwest::get(&url).await.map_err(|e| {
+                    PyRuntimeError::new_err(format!("failed to retrieve file {e:?}"))
+                })?;
+                if !response.status().is_success() {
+                    return Err(PyRuntimeError::new_err(format!(
+                        "failed to retrieve file {url}: status {}",
+                        response.status()
+                    )));
+                }
+                let header = |name| {
+                    response
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string)
+                };
+                let content_type = header(CONTENT_TYPE);
+                let last_modified = header(LAST_MODIFIED);
+                let data = response.bytes().await.map_err(|e| {
+                    PyRuntimeError::new_err(format!("failed to retrieve file {e:?}"))
+                })?;
+                Ok((data.to_vec(), content_type, last_modified))
+            })
+        }
+    }
+
     m.add_class::<ShadowDriveClient>()?;
 
     Ok(())
@@ -0,0 +1,98 @@
+//! Benchmarks for the hashing and upload fan-out paths.
+//!
+//! ```text
+//! cargo bench -p shadow-drive-sdk --features test-utilities
+//! ```
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::StreamExt;
+use shadow_drive_sdk::{
+    merkle::merkle_root, models::ShadowFile, test_utilities::MemoryObjectStore, Pubkey,
+    UploadLimits,
+};
+
+/// Size of each file in the hashing and upload benchmarks.
+const FILE_SIZE: usize = 64 * 1024;
+
+fn files(count: usize) -> Vec<ShadowFile> {
+    (0..count)
+        .map(|i| ShadowFile::bytes(format!("{}.json", i), vec![i as u8; FILE_SIZE]))
+        .collect()
+}
+
+fn sha256_files(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+    let mut group = c.benchmark_group("sha256_files");
+    for count in [1, 100, 1000] {
+        let files = files(count);
+        group.throughput(Throughput::Bytes((count * FILE_SIZE) as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &files, |b, files| {
+            b.to_async(&runtime).iter(|| async move {
+                let mut digests = Vec::with_capacity(files.len());
+                for file in files {
+                    digests.push(file.sha256_digest().await.unwrap());
+                }
+                digests
+            })
+        });
+    }
+    group.finish();
+}
+
+fn merkle_root_files(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+    let mut group = c.benchmark_group("merkle_root");
+    for count in [1, 100, 1000] {
+        let hashes: Vec<(String, [u8; 32])> = runtime.block_on(async {
+            let mut hashes = Vec::with_capacity(count);
+            for file in files(count) {
+                hashes.push((file.name.clone(), file.sha256_digest().await.unwrap()));
+            }
+            hashes
+        });
+        group.bench_with_input(BenchmarkId::from_parameter(count), &hashes, |b, hashes| {
+            b.iter(|| merkle_root(hashes))
+        });
+    }
+    group.finish();
+}
+
+/// Uploads through the same `UploadLimits` fan-out as `store_files_stream`,
+/// with an in-memory store standing in for the upload server.
+fn upload_fan_out(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+    let bucket = Pubkey::new_unique();
+    let mut group = c.benchmark_group("upload_fan_out");
+    for (label, limits) in [
+        ("default", UploadLimits::default()),
+        (
+            "byte_budget",
+            UploadLimits {
+                max_inflight_bytes: Some(4 * FILE_SIZE as u64),
+                ..UploadLimits::default()
+            },
+        ),
+    ] {
+        for count in [10, 100] {
+            group.bench_with_input(BenchmarkId::new(label, count), &count, |b, &count| {
+                b.to_async(&runtime).iter_batched(
+                    || files(count),
+                    |files| {
+                        let limits = &limits;
+                        async move {
+                            let store = MemoryObjectStore::new();
+                            limits
+                                .upload_to(&store, &bucket, files)
+                                .collect::<Vec<_>>()
+                                .await
+                        }
+                    },
+                    criterion::BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sha256_files, merkle_root_files, upload_fan_out);
+criterion_main!(benches);
@@ -1,5 +1,7 @@
 use byte_unit::Byte;
-use shadow_drive_sdk::{models::ShadowFile, ShadowDriveClient, StorageAccountVersion};
+use shadow_drive_sdk::{
+    models::ShadowFile, ShadowDriveClient, StorageAccountFilter, StorageAccountVersion,
+};
 use solana_sdk::{
     pubkey,
     pubkey::Pubkey,
@@ -32,7 +34,7 @@ async fn get_storage_accounts_test<T: Signer>(
     pubkey: &Pubkey,
 ) {
     let storage_accounts = shdw_drive_client
-        .get_storage_accounts(pubkey)
+        .get_storage_accounts(pubkey, StorageAccountFilter::All)
         .await
         .expect("failed to get storage account");
     println!("{:?}", storage_accounts);
@@ -0,0 +1,117 @@
+//! End-to-end smoke test of the SDK against a live cluster.
+//!
+//! Creates a storage account, uploads a small file, lists and downloads it,
+//! checks its contents, then deletes the file and the account.
+//!
+//! ```text
+//! SHDW_SMOKE_KEYPAIR=~/.config/solana/devnet.json \
+//!     cargo run -p shadow-drive-sdk --example smoke_test --features live-test
+//! ```
+//!
+//! * `SHDW_SMOKE_KEYPAIR` - Path to a keypair funded with SOL and SHDW on the target cluster. Required.
+//! * `SHDW_SMOKE_RPC` - RPC URL of the target cluster. Defaults to devnet.
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+use shadow_drive_sdk::{
+    models::ShadowFile, Byte, Keypair, Pubkey, ShadowDriveClient, StorageAccountVersion,
+};
+use solana_sdk::signature::read_keypair_file;
+
+const SOLANA_DEVNET_RPC: &str = "https://api.devnet.solana.com";
+const SMOKE_FILE_NAME: &str = "smoke-test.txt";
+
+#[tokio::main]
+async fn main() {
+    let keypair_file = std::env::var("SHDW_SMOKE_KEYPAIR").expect("SHDW_SMOKE_KEYPAIR is not set");
+    let rpc_url = std::env::var("SHDW_SMOKE_RPC").unwrap_or_else(|_| SOLANA_DEVNET_RPC.to_string());
+    let keypair: Keypair = read_keypair_file(keypair_file).expect("failed to read keypair file");
+    let client = ShadowDriveClient::new(keypair, rpc_url);
+    println!("initialized client");
+
+    // Create account
+    let response = client
+        .create_storage_account(
+            "smoke-test",
+            Byte::from_bytes(2_u128.pow(20)),
+            StorageAccountVersion::V2,
+        )
+        .await
+        .expect("failed to create storage account");
+    let account = Pubkey::from_str(
+        &response
+            .shdw_bucket
+            .expect("create response has no storage account"),
+    )
+    .expect("create response has an invalid storage account");
+    println!("created storage account {}", account);
+
+    // Upload
+    let contents = format!("shadow drive smoke test {}", run_nonce());
+    let response = client
+        .store_files(
+            &account,
+            vec![ShadowFile::bytes(
+                SMOKE_FILE_NAME.to_string(),
+                contents.clone().into_bytes(),
+            )],
+        )
+        .await
+        .expect("failed to upload file");
+    assert!(
+        response.upload_errors.is_empty(),
+        "upload errors: {:?}",
+        response.upload_errors
+    );
+    let url = response
+        .finalized_url_for(SMOKE_FILE_NAME)
+        .expect("uploaded file has no finalized location")
+        .to_string();
+    println!("uploaded {}", url);
+
+    // List
+    let files = client
+        .list_objects(&account)
+        .await
+        .expect("failed to list files");
+    assert!(
+        files.iter().any(|file| file == SMOKE_FILE_NAME),
+        "uploaded file is not listed: {:?}",
+        files
+    );
+    println!("listed {} files", files.len());
+
+    // Download and verify
+    let text = client
+        .get_text(&account, SMOKE_FILE_NAME)
+        .await
+        .expect("failed to download file");
+    assert_eq!(
+        Sha256::digest(text.content.as_bytes()),
+        Sha256::digest(contents.as_bytes()),
+        "downloaded contents differ from the upload"
+    );
+    println!("verified contents");
+
+    // Clean up
+    client
+        .delete_file(&account, url)
+        .await
+        .expect("failed to delete file");
+    println!("deleted file");
+    client
+        .delete_storage_account(&account)
+        .await
+        .expect("failed to delete storage account");
+    println!("deleted storage account");
+
+    println!("smoke test passed");
+}
+
+/// Distinguishes the contents of separate runs, so a stale object can't pass verification.
+fn run_nonce() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default()
+}
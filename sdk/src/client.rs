@@ -1,60 +1,195 @@
 use serde::de::DeserializeOwned;
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use serde_json::{json, Value};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{commitment_config::CommitmentConfig, signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, compute_budget::ComputeBudgetInstruction,
+    epoch_info::EpochInfo, instruction::Instruction, pubkey::Pubkey, signature::Signature,
+    signer::Signer, transaction::Transaction,
+};
+use tracing::Instrument;
 
+mod account_cache;
+mod account_reachable;
 mod add_immutable_storage;
 mod add_storage;
+mod append_to_file;
 mod cancel_delete_storage_account;
 mod claim_stake;
+mod crank;
 mod create_storage_account;
 mod delete_file;
 mod delete_storage_account;
 mod edit_file;
+mod estimate_transaction_fee;
+mod file_versions;
+#[cfg(feature = "nft")]
+mod get_mint_status;
+mod get_object_data_many;
+mod get_program_version;
+mod get_shdw_balance;
 mod get_storage_account;
+mod get_storage_config;
+mod get_text;
 mod list_objects;
+mod list_prunable_accounts;
 mod make_storage_immutable;
 mod migrate;
+mod mirror_directory;
+mod move_all_files;
+mod object_store;
+mod ping_upload_server;
 mod redeem_rent;
 mod reduce_storage;
 mod refresh_stake;
+mod resize_storage_to;
+mod retry;
+mod storage_account_url;
 mod store_files;
+mod store_files_and_await;
+mod store_files_if_changed;
+#[cfg(feature = "pubsub")]
+mod subscribe_account;
 mod top_up;
+mod uploader;
+mod wait_for_confirmation;
+mod warm_up;
 // mod upload_multiple_files;
 
+use account_cache::AccountCache;
+
 use crate::{
+    clock::EpochSource,
     constants::SHDW_DRIVE_ENDPOINT,
     error::Error,
     models::{FileDataResponse, GetBucketSizeResponse, ShadowDriveResult},
 };
+pub use account_reachable::*;
 pub use add_immutable_storage::*;
 pub use add_storage::*;
+pub use append_to_file::*;
 pub use cancel_delete_storage_account::*;
 pub use claim_stake::*;
+pub use crank::*;
 pub use create_storage_account::*;
 pub use delete_file::*;
 pub use delete_storage_account::*;
 pub use edit_file::*;
+pub use estimate_transaction_fee::*;
+pub use file_versions::*;
+#[cfg(feature = "nft")]
+pub use get_mint_status::*;
+pub use get_object_data_many::*;
+pub use get_program_version::*;
+pub use get_shdw_balance::*;
 pub use get_storage_account::*;
+pub use get_storage_config::*;
+pub use get_text::*;
 pub use list_objects::*;
+pub use list_prunable_accounts::*;
 pub use make_storage_immutable::*;
 pub use migrate::*;
+pub use mirror_directory::*;
+pub use move_all_files::*;
+pub use ping_upload_server::*;
 pub use redeem_rent::*;
 pub use reduce_storage::*;
 pub use refresh_stake::*;
+pub use resize_storage_to::*;
+pub use retry::*;
+pub use storage_account_url::*;
 pub use store_files::*;
+pub use store_files_and_await::*;
+pub use store_files_if_changed::*;
+#[cfg(feature = "pubsub")]
+pub use subscribe_account::*;
 pub use top_up::*;
+pub use uploader::*;
+pub use wait_for_confirmation::*;
+pub use warm_up::*;
+
+/// Timeout of the Solana RPC clients the SDK constructs itself.
+const RPC_TIMEOUT: Duration = Duration::from_secs(120);
 
 /// Client that allows a user to interact with the Shadow Drive.
+///
+/// Cloning is cheap when the signer is: clones share the Solana [`RpcClient`], the HTTP
+/// connection pool and the account cache, and copy the remaining settings. A setting changed
+/// with a `with_*` builder after cloning only affects that clone. To share a client whose
+/// signer is not [`Clone`], such as a [`Keypair`](solana_sdk::signer::keypair::Keypair),
+/// wrap the client itself in an [`Arc`]. Dropping a clone never closes connections still
+/// used by other clones.
 pub struct ShadowDriveClient<T>
 where
     T: Signer,
 {
     wallet: T,
-    rpc_client: RpcClient,
+    rpc_client: Arc<RpcClient>,
     http_client: reqwest::Client,
+    epoch_source: Option<Arc<dyn EpochSource>>,
+    account_cache: Option<Arc<AccountCache>>,
+    upload_limits: UploadLimits,
+    priority_fee: u64,
+    retry_policy: RetryPolicy,
+    uploader: UploaderConfig,
+}
+
+impl<T> Clone for ShadowDriveClient<T>
+where
+    T: Signer + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            wallet: self.wallet.clone(),
+            rpc_client: Arc::clone(&self.rpc_client),
+            http_client: self.http_client.clone(),
+            epoch_source: self.epoch_source.clone(),
+            account_cache: self.account_cache.clone(),
+            upload_limits: self.upload_limits.clone(),
+            priority_fee: self.priority_fee,
+            retry_policy: self.retry_policy,
+            uploader: self.uploader.clone(),
+        }
+    }
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer + Clone,
+{
+    /// Returns a clone of the client whose Solana RPC requests use `commitment`, for example
+    /// a `processed` view for fast reads next to a `finalized` one for writes that must stick.
+    /// The clone shares the HTTP connection pool and account cache, and keeps every other setting.
+    ///
+    /// The Solana [`RpcClient`] fixes its commitment when it is built, so the clone gets a new one
+    /// for the same URL, with the timeout used by [`new`](Self::new). Custom transports given to
+    /// [`new_with_rpc`](Self::new_with_rpc), such as auth headers, are not carried over; build
+    /// the client with `new_with_rpc` again to keep them. A signer that is not [`Clone`] can be
+    /// shared by wrapping it in an [`Arc`].
+    /// * `commitment` - The commitment for the clone's RPC requests.
+    ///
+    /// # Example
+    /// ```
+    /// use solana_sdk::{commitment_config::CommitmentConfig, signer::keypair::Keypair};
+    /// use std::sync::Arc;
+    ///
+    /// let wallet = Arc::new(Keypair::generate());
+    /// let shdw_drive = ShadowDriveClient::new(wallet, "https://ssc-dao.genesysgo.net");
+    /// let finalized = shdw_drive.with_commitment(CommitmentConfig::finalized());
+    /// ```
+    #[must_use]
+    pub fn with_commitment(&self, commitment: CommitmentConfig) -> Self {
+        let rpc_client = RpcClient::new_with_timeout_and_commitment(
+            self.rpc_client.url(),
+            RPC_TIMEOUT,
+            commitment,
+        );
+        Self {
+            rpc_client: Arc::new(rpc_client),
+            ..self.clone()
+        }
+    }
 }
 
 impl<T> ShadowDriveClient<T>
@@ -81,13 +216,19 @@ where
     pub fn new<U: ToString>(wallet: T, rpc_url: U) -> Self {
         let rpc_client = RpcClient::new_with_timeout_and_commitment(
             rpc_url.to_string(),
-            Duration::from_secs(120),
+            RPC_TIMEOUT,
             CommitmentConfig::confirmed(),
         );
         Self {
             wallet,
-            rpc_client,
+            rpc_client: Arc::new(rpc_client),
             http_client: reqwest::Client::new(),
+            epoch_source: None,
+            account_cache: None,
+            upload_limits: UploadLimits::default(),
+            priority_fee: 0,
+            retry_policy: RetryPolicy::default(),
+            uploader: UploaderConfig::default(),
         }
     }
 
@@ -110,8 +251,165 @@ where
     pub fn new_with_rpc(wallet: T, rpc_client: RpcClient) -> Self {
         Self {
             wallet,
-            rpc_client,
+            rpc_client: Arc::new(rpc_client),
             http_client: reqwest::Client::new(),
+            epoch_source: None,
+            account_cache: None,
+            upload_limits: UploadLimits::default(),
+            priority_fee: 0,
+            retry_policy: RetryPolicy::default(),
+            uploader: UploaderConfig::default(),
+        }
+    }
+
+    /// Returns a client that signs with `wallet` but otherwise shares this client's Solana
+    /// [`RpcClient`], HTTP connection pool and account cache, and copies its settings.
+    /// Use it to rotate keys in a long-lived process without reconnecting.
+    ///
+    /// `self` is left unchanged: operations already running on it, or started on it later,
+    /// keep signing with the old key. To rotate the key of a client shared between tasks,
+    /// keep it behind a lock such as `Arc<RwLock<ShadowDriveClient<T>>>` and replace it
+    /// with the rotated client; tasks holding a read guard finish with the old key.
+    /// * `wallet` - The [`Signer`] for all transactions and messages signed by the new client.
+    ///
+    /// # Example
+    /// ```
+    /// use solana_sdk::signer::keypair::Keypair;
+    ///
+    /// let shdw_drive = ShadowDriveClient::new(Keypair::generate(), "https://ssc-dao.genesysgo.net");
+    /// let rotated = shdw_drive.rotate_signer(Keypair::generate());
+    /// ```
+    pub fn rotate_signer<U: Signer>(&self, wallet: U) -> ShadowDriveClient<U> {
+        ShadowDriveClient {
+            wallet,
+            rpc_client: Arc::clone(&self.rpc_client),
+            http_client: self.http_client.clone(),
+            epoch_source: self.epoch_source.clone(),
+            account_cache: self.account_cache.clone(),
+            upload_limits: self.upload_limits.clone(),
+            priority_fee: self.priority_fee,
+            retry_policy: self.retry_policy,
+            uploader: self.uploader.clone(),
+        }
+    }
+
+    /// Overrides where the client reads the current epoch from. By default the
+    /// client's [`RpcClient`] is used. Mainly useful for testing time-dependent
+    /// logic such as [`is_stake_claimable`](Self::is_stake_claimable) against a fixed epoch.
+    ///
+    /// # Example
+    /// ```
+    /// use shadow_drive_rust::clock::FixedEpoch;
+    /// use solana_sdk::signer::keypair::Keypair;
+    ///
+    /// let wallet = Keypair::generate();
+    /// let shdw_drive = ShadowDriveClient::new(wallet, "https://ssc-dao.genesysgo.net")
+    ///     .with_epoch_source(FixedEpoch(400));
+    /// ```
+    #[must_use]
+    pub fn with_epoch_source<E: EpochSource + 'static>(mut self, epoch_source: E) -> Self {
+        self.epoch_source = Some(Arc::new(epoch_source));
+        self
+    }
+
+    /// Sets how many files, and how many bytes of file contents, concurrent uploads
+    /// such as [`store_files_stream`](Self::store_files_stream) may have in flight at once.
+    /// See [`UploadLimits`] for how the two limits interact.
+    ///
+    /// # Example
+    /// ```
+    /// use shadow_drive_rust::UploadLimits;
+    /// use solana_sdk::signer::keypair::Keypair;
+    ///
+    /// let wallet = Keypair::generate();
+    /// let shdw_drive = ShadowDriveClient::new(wallet, "https://ssc-dao.genesysgo.net")
+    ///     .with_upload_limits(UploadLimits {
+    ///         max_concurrency: 8,
+    ///         max_inflight_bytes: Some(256 * 1024 * 1024),
+    ///         ..Default::default()
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn with_upload_limits(mut self, upload_limits: UploadLimits) -> Self {
+        self.upload_limits = upload_limits;
+        self
+    }
+
+    /// Sets a priority fee, in micro-lamports per compute unit, on every transaction the client sends.
+    /// Prioritized transactions are more likely to land quickly when the cluster is congested.
+    /// Defaults to 0, which adds no compute budget instruction. Recent fees paid for
+    /// comparable transactions can be looked up with the `getRecentPrioritizationFees` RPC method.
+    ///
+    /// # Example
+    /// ```
+    /// use solana_sdk::signer::keypair::Keypair;
+    ///
+    /// let wallet = Keypair::generate();
+    /// let shdw_drive = ShadowDriveClient::new(wallet, "https://ssc-dao.genesysgo.net")
+    ///     .with_priority_fee(10_000);
+    /// ```
+    #[must_use]
+    pub fn with_priority_fee(mut self, micro_lamports: u64) -> Self {
+        self.priority_fee = micro_lamports;
+        self
+    }
+
+    /// Prepends a compute unit price instruction to `instructions` if a priority fee is set.
+    pub(crate) fn prepend_priority_fee(&self, instructions: &[Instruction]) -> Vec<Instruction> {
+        let priority_fee = (self.priority_fee > 0)
+            .then(|| ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee));
+        priority_fee
+            .into_iter()
+            .chain(instructions.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the current epoch, slot, and progress through the epoch from the Solana RPC.
+    /// This always queries the cluster, even when an [`EpochSource`] is configured
+    /// with [`with_epoch_source`](Self::with_epoch_source).
+    ///
+    /// # Example
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::keypair::read_keypair_file;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let epoch_info = shdw_drive_client.get_epoch_info().await?;
+    /// let slots_left = epoch_info.slots_in_epoch - epoch_info.slot_index;
+    /// ```
+    pub async fn get_epoch_info(&self) -> ShadowDriveResult<EpochInfo> {
+        Ok(self.rpc_client.get_epoch_info().await?)
+    }
+
+    /// Returns the SOL balance of an account, in lamports.
+    /// * `owner` - The account to look up.
+    ///
+    /// # Example
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::{keypair::read_keypair_file, Signer};
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let lamports = shdw_drive_client.get_sol_balance(&user_pubkey).await?;
+    /// ```
+    pub async fn get_sol_balance(&self, owner: &Pubkey) -> ShadowDriveResult<u64> {
+        Ok(self.rpc_client.get_balance(owner).await?)
+    }
+
+    /// Returns the current epoch from the configured [`EpochSource`].
+    pub(crate) async fn current_epoch(&self) -> ShadowDriveResult<u64> {
+        match &self.epoch_source {
+            Some(epoch_source) => epoch_source.current_epoch().await,
+            None => Ok(self.get_epoch_info().await?.epoch),
         }
     }
 
@@ -161,6 +459,18 @@ where
         Ok(response)
     }
 
+    /// Sends a signed transaction through the client's RPC and waits for confirmation.
+    async fn send_and_confirm_transaction(
+        &self,
+        txn: &Transaction,
+    ) -> ShadowDriveResult<Signature> {
+        Ok(self
+            .rpc_client
+            .send_and_confirm_transaction(txn)
+            .instrument(tracing::info_span!("transaction_confirm"))
+            .await?)
+    }
+
     async fn send_shdw_txn<K: DeserializeOwned>(
         &self,
         uri: &str,
@@ -176,10 +486,11 @@ where
 
         let response = self
             .http_client
-            .post(format!("{}/{}", SHDW_DRIVE_ENDPOINT, uri))
+            .post(format!("{}/{}", self.uploader.endpoint, uri))
             .header("Content-Type", "application/json")
             .body(body)
             .send()
+            .instrument(tracing::info_span!("transaction_confirm"))
             .await?;
 
         if !response.status().is_success() {
@@ -0,0 +1,76 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use super::ShadowDriveClient;
+use crate::models::StorageAccountInfo;
+
+/// Per-account cache of [`get_storage_account`](ShadowDriveClient::get_storage_account) results.
+pub(crate) struct AccountCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<Pubkey, (Instant, StorageAccountInfo)>>,
+}
+
+impl AccountCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &Pubkey) -> Option<StorageAccountInfo> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries
+            .get(key)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl)
+            .map(|(_, account)| account.clone())
+    }
+
+    pub(crate) fn insert(&self, key: Pubkey, account: StorageAccountInfo) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.insert(key, (Instant::now(), account));
+    }
+
+    fn remove(&self, key: &Pubkey) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.remove(key);
+    }
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Caches the result of [`get_storage_account`](Self::get_storage_account) per account for `ttl`.
+    /// The cache is shared with clones of the client made after this call.
+    /// Call [`invalidate_account_cache`](Self::invalidate_account_cache) after modifying an account
+    /// to make the next fetch reflect the change.
+    ///
+    /// # Example
+    /// ```
+    /// use solana_sdk::signer::keypair::Keypair;
+    /// use std::time::Duration;
+    ///
+    /// let wallet = Keypair::generate();
+    /// let shdw_drive = ShadowDriveClient::new(wallet, "https://ssc-dao.genesysgo.net")
+    ///     .with_account_cache(Duration::from_secs(30));
+    /// ```
+    #[must_use]
+    pub fn with_account_cache(mut self, ttl: Duration) -> Self {
+        self.account_cache = Some(Arc::new(AccountCache::new(ttl)));
+        self
+    }
+
+    /// Drops the cached copy of a storage account, if any, so the next
+    /// [`get_storage_account`](Self::get_storage_account) fetches it again.
+    /// * `key` - The public key of the storage account.
+    pub fn invalidate_account_cache(&self, key: &Pubkey) {
+        if let Some(cache) = &self.account_cache {
+            cache.remove(key);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::{error::Error, models::ShadowDriveResult};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Checks whether a storage account's files are being served, as opposed to the account
+    /// merely existing on-chain. Right after creation, the gateway may not serve a new account yet.
+    /// The account must be listable by the upload server and, if it holds any files,
+    /// its first file must be fetchable from its public URL.
+    /// Returns `Ok(false)` when the server answers with an error status, and `Err` only when it
+    /// can't be reached at all.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to probe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// if shdw_drive_client.account_reachable(&storage_account_key).await? {
+    ///     println!("{}", shdw_drive_client.storage_account_url(&storage_account_key));
+    /// }
+    /// ```
+    pub async fn account_reachable(&self, storage_account_key: &Pubkey) -> ShadowDriveResult<bool> {
+        let names = match self.list_objects(storage_account_key).await {
+            Ok(names) => names,
+            Err(Error::ShadowDriveServerError { .. }) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let name = match names.first() {
+            Some(name) => name,
+            None => return Ok(true),
+        };
+        let response = self
+            .http_client
+            .head(format!(
+                "{}/{}",
+                self.storage_account_url(storage_account_key),
+                name
+            ))
+            .send()
+            .await?;
+        Ok(response.status().is_success())
+    }
+}
@@ -13,7 +13,7 @@ use super::ShadowDriveClient;
 use crate::models::storage_acct::{StorageAccount, StorageAccountV2, StorageAcct};
 use crate::serialize_and_encode;
 use crate::{
-    constants::{EMISSIONS, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT, UPLOADER},
+    constants::{EMISSIONS, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT},
     error::Error,
     models::*,
 };
@@ -28,7 +28,7 @@ where
     /// * `size` - The additional amount of storage you want to add.
     /// E.g if you have an existing [`StorageAccount`](crate::models::StorageAccount) with 1MB of storage
     /// but you need 2MB total, `size` should equal 1MB.
-    /// When specifying size, only KB, MB, and GB storage units are currently supported.
+    /// Sizes built with [`parse_storage_size`](crate::units::parse_storage_size) treat KB, MB and GB as binary units, matching on-chain GiB pricing.
     /// # Example
     ///
     /// ```
@@ -47,7 +47,7 @@ where
     /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
     /// #
     /// let add_immutable_storage_response = shdw_drive_client
-    ///     .add_immutable_storage(storage_account_key, Byte::from_str("1MB").expect("invalid byte string"))
+    ///     .add_immutable_storage(storage_account_key, parse_storage_size("1MB").expect("invalid size string"))
     ///     .await?;
     /// ```
     pub async fn add_immutable_storage(
@@ -60,7 +60,10 @@ where
             .try_into()
             .map_err(|_| Error::InvalidStorage)?;
 
-        let selected_storage_acct = self.get_storage_account(storage_account_key).await?;
+        let selected_storage_acct = self
+            .get_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
 
         let txn_encoded = match selected_storage_acct {
             StorageAcct::V1(storage_account) => {
@@ -98,7 +101,7 @@ where
             emissions_wallet: emissions_ata,
             owner: storage_account.owner_1,
             owner_ata,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
             token_program: TokenProgramID,
@@ -113,7 +116,10 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(wallet_pubkey),
+        );
 
         txn.try_partial_sign(
             &[&self.wallet],
@@ -141,7 +147,7 @@ where
             emissions_wallet: emissions_ata,
             owner: storage_account.owner_1,
             owner_ata,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
             token_program: TokenProgramID,
@@ -157,7 +163,10 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(wallet_pubkey),
+        );
 
         txn.try_partial_sign(
             &[&self.wallet],
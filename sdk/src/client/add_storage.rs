@@ -16,7 +16,6 @@ use std::collections::HashMap;
 
 use super::ShadowDriveClient;
 use crate::constants::SHDW_DRIVE_ENDPOINT;
-use crate::constants::UPLOADER;
 use crate::models::storage_acct::{StorageAccount, StorageAccountV2, StorageAcct};
 use crate::models::GetBucketSizeResponse;
 use crate::serialize_and_encode;
@@ -32,15 +31,25 @@ where
     T: Signer,
 {
     /// Adds storage capacity to the specified [`StorageAccount`](crate::models::StorageAccount).
+    /// Fails with [`Error::AccountMarkedForDeletion`] if the account is marked for deletion.
     /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount).
     /// * `size` - The additional amount of storage you want to add.
     /// E.g if you have an existing [`StorageAccount`](crate::models::StorageAccount) with 1MB of storage
     /// but you need 2MB total, `size` should equal 1MB.
-    /// When specifying size, only KB, MB, and GB storage units are currently supported.
+    /// Sizes built with [`parse_storage_size`](crate::units::parse_storage_size) treat KB, MB and GB as binary units, matching on-chain GiB pricing.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::AccountMarkedForDeletion`] if the account is marked for deletion.
+    /// - [`Error::UserInfoNotCreated`] if the owner has never created a storage account.
+    /// - [`Error::InvalidStorage`] if `size` does not fit in a `u64`.
+    /// - [`Error::ShadowDriveServerError`] if the upload server rejects the transaction.
+    /// - [`Error::SolanaRpcError`] or [`Error::ReqwestError`] on network failures.
+    ///
     /// # Example
     ///
     /// ```
-    /// # use byte_unit::Byte;
+    /// # use shadow_drive_rust::units::parse_storage_size;
     /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
     /// # use solana_client::rpc_client::RpcClient;
     /// # use solana_sdk::{
@@ -54,7 +63,7 @@ where
     /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
     /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
     /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
-    /// # let added_bytes = Byte::from_str("1MB").expect("invalid byte string");
+    /// # let added_bytes = parse_storage_size("1MB").expect("invalid size string");
     /// #
     /// let add_storage_response = shdw_drive_client
     ///     .add_storage(&storage_account_key, added_bytes)
@@ -93,7 +102,10 @@ where
             }
         }
 
-        let selected_storage_acct = self.get_storage_account(storage_account_key).await?;
+        let selected_storage_acct = self
+            .writable_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
         let mut bucket_query = HashMap::new();
         bucket_query.insert("storageAccount", storage_account_key.to_string());
 
@@ -144,7 +156,7 @@ where
             owner: storage_account.owner_1,
             owner_ata,
             stake_account,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
             token_program: TokenProgramID,
@@ -159,7 +171,10 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(wallet_pubkey),
+        );
 
         txn.try_partial_sign(
             &[&self.wallet],
@@ -178,16 +193,44 @@ where
         size_as_bytes: u64,
     ) -> ShadowDriveResult<String> {
         let wallet_pubkey = &self.wallet.pubkey();
-        let owner_ata = get_associated_token_address(wallet_pubkey, &TOKEN_MINT);
+        let instruction = self.add_storage_v2_instruction(
+            storage_account_key,
+            storage_account.owner_1,
+            size_as_bytes,
+        );
+
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(wallet_pubkey),
+        );
+
+        txn.try_partial_sign(
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        )?;
+
+        let txn_encoded = serialize_and_encode(&txn)?;
+
+        Ok(txn_encoded)
+    }
+
+    /// Builds the instruction that [`add_storage`](Self::add_storage) sends for a v2 account.
+    pub(crate) fn add_storage_v2_instruction(
+        &self,
+        storage_account_key: &Pubkey,
+        owner: Pubkey,
+        size_as_bytes: u64,
+    ) -> Instruction {
+        let owner_ata = get_associated_token_address(&self.wallet.pubkey(), &TOKEN_MINT);
         let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
 
         let accounts = shdw_drive_accounts::IncreaseStorageV2 {
             storage_config: *STORAGE_CONFIG_PDA,
             storage_account: *storage_account_key,
-            owner: storage_account.owner_1,
+            owner,
             owner_ata,
             stake_account,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
             token_program: TokenProgramID,
@@ -197,21 +240,10 @@ where
             additional_storage: size_as_bytes,
         };
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(wallet_pubkey));
-
-        txn.try_partial_sign(
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        )?;
-
-        let txn_encoded = serialize_and_encode(&txn)?;
-
-        Ok(txn_encoded)
+        }
     }
 }
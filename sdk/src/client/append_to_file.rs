@@ -0,0 +1,60 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::models::{ShadowDriveResult, ShadowFile};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Appends `bytes` to the end of an existing file and returns the file's new size in bytes.
+    ///
+    /// The Shadow Drive gateway has no partial-write endpoint, so this downloads the current
+    /// contents, appends `bytes` locally, and replaces the file with [`edit_file`](Self::edit_file).
+    /// The whole file is transferred in both directions, and the operation is not atomic:
+    /// concurrent writers to the same file can overwrite each other's appends, and a failed
+    /// upload leaves the file unchanged.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that contains the file.
+    /// * `filename` - The name of the file to append to.
+    /// * `bytes` - The data to append.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let new_size = shdw_drive_client
+    ///     .append_to_file(&storage_account_key, "events.log", b"started\n")
+    ///     .await?;
+    /// ```
+    pub async fn append_to_file(
+        &self,
+        storage_account_key: &Pubkey,
+        filename: &str,
+        bytes: &[u8],
+    ) -> ShadowDriveResult<u64> {
+        let (mut data, _) = self.get_object(storage_account_key, filename).await?;
+        data.extend_from_slice(bytes);
+        let new_size = data.len() as u64;
+
+        self.edit_file(
+            storage_account_key,
+            ShadowFile::bytes(filename.to_string(), data),
+        )
+        .await?;
+
+        Ok(new_size)
+    }
+}
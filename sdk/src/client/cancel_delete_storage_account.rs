@@ -12,6 +12,7 @@ use crate::models::storage_acct::StorageAcct;
 use crate::{
     constants::{PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT},
     derived_addresses,
+    error::Error,
     models::{
         storage_acct::{StorageAccount, StorageAccountV2},
         *,
@@ -24,6 +25,7 @@ where
 {
     /// Unmarks a [`StorageAccount`](crate::models::StorageAccount) for deletion from the Shadow Drive.
     /// To prevent deletion, this method must be called before the end of the Solana epoch in which `delete_storage_account` is called.
+    /// Returns [`Error::AccountNotMarkedForDeletion`] without sending a transaction if the account is not marked for deletion.
     /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that you want to unmark for deletion.
     /// # Example
     ///
@@ -51,8 +53,11 @@ where
         storage_account_key: &Pubkey,
     ) -> ShadowDriveResult<ShdwDriveResponse> {
         let selected_account = self.get_storage_account(storage_account_key).await?;
+        if !selected_account.to_be_deleted() {
+            return Err(Error::AccountNotMarkedForDeletion(*storage_account_key));
+        }
 
-        let txn = match selected_account {
+        let txn = match selected_account.into_versioned() {
             StorageAcct::V1(v1) => {
                 self.cancel_delete_storage_account_v1(storage_account_key, v1)
                     .await?
@@ -63,7 +68,7 @@ where
             }
         };
 
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
 
         Ok(ShdwDriveResponse {
             txid: txn_result.to_string(),
@@ -95,7 +100,7 @@ where
         };
 
         let txn = Transaction::new_signed_with_payer(
-            &[instruction],
+            &self.prepend_priority_fee(&[instruction]),
             Some(&self.wallet.pubkey()),
             &[&self.wallet],
             self.rpc_client.get_latest_blockhash().await?,
@@ -109,32 +114,45 @@ where
         storage_account_key: &Pubkey,
         storage_account: StorageAccountV2,
     ) -> ShadowDriveResult<Transaction> {
+        let instruction = self.cancel_delete_storage_account_v2_instruction(
+            storage_account_key,
+            storage_account.owner_1,
+        );
+
+        let txn = Transaction::new_signed_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&self.wallet.pubkey()),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        );
+
+        Ok(txn)
+    }
+
+    /// Builds the instruction that
+    /// [`cancel_delete_storage_account`](Self::cancel_delete_storage_account) sends for a v2 account.
+    pub(crate) fn cancel_delete_storage_account_v2_instruction(
+        &self,
+        storage_account_key: &Pubkey,
+        owner: Pubkey,
+    ) -> Instruction {
         let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
 
         let accounts = shdw_drive_accounts::UnmarkDeleteAccountV2 {
             storage_config: *STORAGE_CONFIG_PDA,
             storage_account: *storage_account_key,
             stake_account,
-            owner: storage_account.owner_1,
+            owner,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
         };
 
         let args = UnmarkDeleteAccount2 {};
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let txn = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&self.wallet.pubkey()),
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
-
-        Ok(txn)
+        }
     }
 }
@@ -1,4 +1,4 @@
-use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
 use shadow_drive_user_staking::accounts as shdw_drive_accounts;
 use shadow_drive_user_staking::instruction as shdw_drive_instructions;
 use solana_sdk::{
@@ -8,14 +8,18 @@ use spl_associated_token_account::get_associated_token_address;
 
 use super::ShadowDriveClient;
 use crate::{
+    clock::stake_claimable,
     constants::{PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT},
     derived_addresses::*,
+    error::Error,
     models::{
         storage_acct::{StorageAccount, StorageAccountV2, StorageAcct},
         *,
     },
 };
 use spl_token::ID as TokenProgramID;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 
 impl<T> ShadowDriveClient<T>
 where
@@ -49,7 +53,10 @@ where
         &self,
         storage_account_key: &Pubkey,
     ) -> ShadowDriveResult<ShdwDriveResponse> {
-        let selected_account = self.get_storage_account(storage_account_key).await?;
+        let selected_account = self
+            .get_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
 
         let txn = match selected_account {
             StorageAcct::V1(storage_account) => {
@@ -62,13 +69,92 @@ where
             }
         };
 
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
 
         Ok(ShdwDriveResponse {
             txid: txn_result.to_string(),
         })
     }
 
+    /// Returns the [`UnstakeInfo`](crate::models::UnstakeInfo) created when storage was last reduced
+    /// on the given [`StorageAccount`](crate::models::StorageAccount).
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount).
+    pub async fn get_unstake_info(
+        &self,
+        storage_account_key: &Pubkey,
+    ) -> ShadowDriveResult<UnstakeInfo> {
+        let unstake_info_key = unstake_info(storage_account_key).0;
+        let data = self
+            .rpc_client
+            .get_account_data(&unstake_info_key)
+            .instrument(tracing::info_span!("rpc_account_fetch"))
+            .await?;
+        Ok(UnstakeInfo::try_deserialize(&mut data.as_slice())?)
+    }
+
+    /// Returns whether the unstake cooldown for the given [`StorageAccount`](crate::models::StorageAccount)
+    /// has elapsed, i.e. whether `claim_stake` can succeed in the current epoch.
+    /// The current epoch is read from the client's [`EpochSource`](crate::clock::EpochSource).
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount).
+    pub async fn is_stake_claimable(
+        &self,
+        storage_account_key: &Pubkey,
+    ) -> ShadowDriveResult<bool> {
+        let unstake_info = self.get_unstake_info(storage_account_key).await?;
+        let current_epoch = self.current_epoch().await?;
+        Ok(stake_claimable(
+            unstake_info.epoch_last_unstaked,
+            current_epoch,
+        ))
+    }
+
+    /// Waits until the unstake cooldown has elapsed, then claims stake as in [`claim_stake`](Self::claim_stake).
+    /// The cooldown ends at the end of the epoch in which `reduce_storage` was called.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that you want to claim excess stake from.
+    /// * `poll_interval` - How long to wait between checks of the current epoch.
+    /// * `timeout` - How long to wait in total before giving up with [`Error::ClaimStakeTimeout`].
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// # use std::time::Duration;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let claim_stake = shdw_drive_client
+    ///     .claim_stake_when_ready(
+    ///         &storage_account_key,
+    ///         Duration::from_secs(60),
+    ///         Duration::from_secs(3 * 24 * 60 * 60),
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn claim_stake_when_ready(
+        &self,
+        storage_account_key: &Pubkey,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> ShadowDriveResult<ShdwDriveResponse> {
+        let start = Instant::now();
+        while !self.is_stake_claimable(storage_account_key).await? {
+            if start.elapsed() + poll_interval > timeout {
+                return Err(Error::ClaimStakeTimeout);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+        self.claim_stake(storage_account_key).await
+    }
+
     async fn claim_stake_v1(
         &self,
         storage_account_key: &Pubkey,
@@ -100,7 +186,7 @@ where
         };
 
         let txn = Transaction::new_signed_with_payer(
-            &[instruction],
+            &self.prepend_priority_fee(&[instruction]),
             Some(&wallet_pubkey),
             &[&self.wallet],
             self.rpc_client.get_latest_blockhash().await?,
@@ -114,6 +200,26 @@ where
         storage_account_key: &Pubkey,
         storage_account: StorageAccountV2,
     ) -> ShadowDriveResult<Transaction> {
+        let wallet_pubkey = self.wallet.pubkey();
+        let instruction =
+            self.claim_stake_v2_instruction(storage_account_key, storage_account.owner_1);
+
+        let txn = Transaction::new_signed_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        );
+
+        Ok(txn)
+    }
+
+    /// Builds the instruction that [`claim_stake`](Self::claim_stake) sends for a v2 account.
+    pub(crate) fn claim_stake_v2_instruction(
+        &self,
+        storage_account_key: &Pubkey,
+        owner: Pubkey,
+    ) -> Instruction {
         let wallet_pubkey = self.wallet.pubkey();
         let unstake_account = unstake_account(storage_account_key).0;
         let unstake_info_account = unstake_info(storage_account_key).0;
@@ -124,7 +230,7 @@ where
             storage_account: *storage_account_key,
             unstake_info: unstake_info_account,
             unstake_account,
-            owner: storage_account.owner_1,
+            owner,
             owner_ata,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
@@ -133,19 +239,10 @@ where
 
         let args = shdw_drive_instructions::ClaimStake2 {};
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let txn = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&wallet_pubkey),
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
-
-        Ok(txn)
+        }
     }
 }
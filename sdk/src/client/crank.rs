@@ -0,0 +1,168 @@
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use futures::{stream, StreamExt};
+use shadow_drive_user_staking::accounts as shdw_drive_accounts;
+use shadow_drive_user_staking::instruction as shdw_drive_instructions;
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signer::Signer, transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::ID as TokenProgramID;
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::{EMISSIONS, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT},
+    derived_addresses,
+    models::{storage_acct::StorageAcct, *},
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Collects outstanding mutable storage fees from a [`StorageAccount`](crate::models::StorageAccount)'s stake.
+    /// Cranking can be performed by anyone; the client's wallet signs and receives the cranker's share.
+    /// The returned [`CrankResponse`] reports the total fees deducted from the stake account.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to crank.
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let crank_response = shdw_drive_client
+    ///     .crank(&storage_account_key)
+    ///     .await?;
+    /// ```
+    pub async fn crank(&self, storage_account_key: &Pubkey) -> ShadowDriveResult<CrankResponse> {
+        let selected_account = self
+            .get_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
+
+        let instruction = match selected_account {
+            StorageAcct::V1(_) => self.crank_v1(storage_account_key),
+            StorageAcct::V2(_) => self.crank_v2(storage_account_key),
+        };
+
+        let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
+        let stake_before = self.token_account_balance(&stake_account).await?;
+
+        let txn = Transaction::new_signed_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&self.wallet.pubkey()),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        );
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
+
+        let stake_after = self.token_account_balance(&stake_account).await?;
+
+        Ok(CrankResponse {
+            txid: txn_result.to_string(),
+            fees_collected: stake_before.saturating_sub(stake_after),
+        })
+    }
+
+    /// Cranks each of the given storage accounts as in [`crank`](Self::crank),
+    /// running at most `concurrency` cranks at a time.
+    /// Results are returned in the same order as `storage_account_keys`.
+    /// * `storage_account_keys` - The public keys of the [`StorageAccount`](crate::models::StorageAccount)s to crank.
+    /// * `concurrency` - Maximum number of cranks in flight at once.
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let storage_account_keys: Vec<_> = (0..10).map(|seed| storage_account(&user_pubkey, seed).0).collect();
+    /// #
+    /// let crank_responses = shdw_drive_client
+    ///     .crank_many(&storage_account_keys, 4)
+    ///     .await;
+    /// ```
+    pub async fn crank_many(
+        &self,
+        storage_account_keys: &[Pubkey],
+        concurrency: usize,
+    ) -> Vec<ShadowDriveResult<CrankResponse>> {
+        stream::iter(storage_account_keys)
+            .map(|storage_account_key| self.crank(storage_account_key))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    fn crank_v1(&self, storage_account_key: &Pubkey) -> Instruction {
+        let wallet_pubkey = self.wallet.pubkey();
+        let cranker_ata = get_associated_token_address(&wallet_pubkey, &TOKEN_MINT);
+        let emissions_ata = get_associated_token_address(&EMISSIONS, &TOKEN_MINT);
+        let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
+
+        let accounts = shdw_drive_accounts::CrankV1 {
+            storage_config: *STORAGE_CONFIG_PDA,
+            storage_account: *storage_account_key,
+            cranker: wallet_pubkey,
+            cranker_ata,
+            stake_account,
+            emissions_wallet: emissions_ata,
+            token_mint: TOKEN_MINT,
+            system_program: system_program::ID,
+            token_program: TokenProgramID,
+        };
+
+        let args = shdw_drive_instructions::Crank {};
+
+        Instruction {
+            program_id: PROGRAM_ADDRESS,
+            accounts: accounts.to_account_metas(None),
+            data: args.data(),
+        }
+    }
+
+    fn crank_v2(&self, storage_account_key: &Pubkey) -> Instruction {
+        let wallet_pubkey = self.wallet.pubkey();
+        let cranker_ata = get_associated_token_address(&wallet_pubkey, &TOKEN_MINT);
+        let emissions_ata = get_associated_token_address(&EMISSIONS, &TOKEN_MINT);
+        let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
+
+        let accounts = shdw_drive_accounts::CrankV2 {
+            storage_config: *STORAGE_CONFIG_PDA,
+            storage_account: *storage_account_key,
+            cranker: wallet_pubkey,
+            cranker_ata,
+            stake_account,
+            emissions_wallet: emissions_ata,
+            token_mint: TOKEN_MINT,
+            system_program: system_program::ID,
+            token_program: TokenProgramID,
+        };
+
+        let args = shdw_drive_instructions::Crank2 {};
+
+        Instruction {
+            program_id: PROGRAM_ADDRESS,
+            accounts: accounts.to_account_metas(None),
+            data: args.data(),
+        }
+    }
+}
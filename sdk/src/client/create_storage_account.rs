@@ -15,15 +15,16 @@ use solana_sdk::{
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::ID as TokenProgram;
 
-use super::ShadowDriveClient;
+use super::{get_storage_config::check_account_size, ShadowDriveClient};
 use crate::{
-    constants::{PROGRAM_ADDRESS, SHDW_DRIVE_ENDPOINT, STORAGE_CONFIG_PDA, TOKEN_MINT, UPLOADER},
+    constants::{MAX_IDENTIFIER_SIZE, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT},
     derived_addresses,
     error::Error,
     models::*,
     serialize_and_encode,
 };
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum StorageAccountVersion {
     V1 { owner_2: Option<Pubkey> },
     V2,
@@ -45,6 +46,51 @@ impl StorageAccountVersion {
     }
 }
 
+impl std::fmt::Display for StorageAccountVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageAccountVersion::V1 { .. } => f.write_str("v1"),
+            StorageAccountVersion::V2 => f.write_str("v2"),
+        }
+    }
+}
+
+/// Checks and normalizes a storage account identifier the way [`create_storage_account`](ShadowDriveClient::create_storage_account) does.
+/// Leading and trailing whitespace is trimmed. The result must be non-empty and contain no control
+/// characters or path separators (`/`, `\\`), otherwise [`Error::InvalidIdentifier`] is returned
+/// with the reason. Like the on-chain `validate_identifier`, it must also be at most
+/// [`MAX_IDENTIFIER_SIZE`] bytes (not characters), otherwise [`Error::IdentifierTooLong`] is returned.
+/// * `name` - The identifier to normalize.
+/// * `lowercase` - Also convert the identifier to lowercase. Identifiers are case-sensitive on-chain,
+/// so this is only useful to keep names consistent, and is off for `create_storage_account`.
+pub fn normalize_identifier(name: &str, lowercase: bool) -> ShadowDriveResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::InvalidIdentifier("identifier is empty".to_string()));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_control() || *c == '/' || *c == '\\')
+    {
+        return Err(Error::InvalidIdentifier(format!(
+            "identifier contains disallowed character {:?}",
+            c
+        )));
+    }
+    let name = if lowercase {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    };
+    if name.len() > MAX_IDENTIFIER_SIZE {
+        return Err(Error::IdentifierTooLong {
+            len: name.len(),
+            max: MAX_IDENTIFIER_SIZE,
+        });
+    }
+    Ok(name)
+}
+
 impl<T> ShadowDriveClient<T>
 where
     T: Signer,
@@ -52,14 +98,27 @@ where
     /// Creates a [`StorageAccount`](crate::models::StorageAccount) on the Shadow Drive.
     /// [`StorageAccount`]'s can hold multiple files, and are paid for using the SHDW token.
     /// * `name` - The name of the [`StorageAccount`](crate::models::StorageAccount). Does not need to be unique.
+    /// Normalized with [`normalize_identifier`] before use, without lowercasing; see there for the rules.
     /// * `size` - The amount of storage the [`StorageAccount`](crate::models::StorageAccount) should be initialized with.
-    /// When specifying size, only KB, MB, and GB storage units are currently supported.
+    /// Sizes built with [`parse_storage_size`](crate::units::parse_storage_size) treat KB, MB and GB as binary units, matching on-chain GiB pricing.
+    /// Must be within the account size bounds of the on-chain [`StorageConfig`](crate::StorageConfig),
+    /// otherwise [`Error::SizeOutOfRange`] is returned before any transaction is sent.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidIdentifier`] or [`Error::IdentifierTooLong`] if `name` is invalid.
+    /// - [`Error::SizeOutOfRange`] if `size` is outside the on-chain bounds.
+    /// - [`Error::ShadowDriveServerError`] if the upload server rejects the transaction.
+    /// - [`Error::SolanaRpcError`], [`Error::AnchorError`] or [`Error::ReqwestError`] if the
+    ///   user info or storage config cannot be read, or on network failures.
     pub async fn create_storage_account(
         &self,
         name: &str,
         size: Byte,
         version: StorageAccountVersion,
     ) -> ShadowDriveResult<CreateStorageAccountResponse> {
+        let name = &normalize_identifier(name, false)?;
+
         let wallet = &self.wallet;
         let wallet_pubkey = wallet.pubkey();
 
@@ -94,6 +153,7 @@ where
             .get_bytes()
             .try_into()
             .map_err(|_| Error::InvalidStorage)?;
+        check_account_size(&self.get_storage_config().await?, storage_requested)?;
 
         let txn_encoded = match version {
             StorageAccountVersion::V1 { owner_2 } => {
@@ -110,7 +170,7 @@ where
 
         let response = self
             .http_client
-            .post(format!("{}/storage-account", SHDW_DRIVE_ENDPOINT))
+            .post(format!("{}/storage-account", self.uploader.endpoint))
             .header("Content-Type", "application/json")
             .body(body)
             .send()
@@ -151,7 +211,7 @@ where
             stake_account,
             token_mint: TOKEN_MINT,
             owner_1: wallet_pubkey,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             owner_1_token_account: owner_ata,
             system_program: system_program::ID,
             token_program: TokenProgram,
@@ -170,7 +230,10 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
 
         txn.try_partial_sign(
             &[&self.wallet],
@@ -190,6 +253,34 @@ where
         storage_requested: u64,
     ) -> ShadowDriveResult<String> {
         let wallet_pubkey = self.wallet.pubkey();
+        let instruction =
+            self.create_v2_instruction(name, account_seed, user_info, storage_requested);
+
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
+
+        txn.try_partial_sign(
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        )?;
+
+        let txn_encoded = serialize_and_encode(&txn)?;
+
+        Ok(txn_encoded)
+    }
+
+    /// Builds the instruction that [`create_storage_account`](Self::create_storage_account)
+    /// sends for a v2 account.
+    pub(crate) fn create_v2_instruction(
+        &self,
+        name: &str,
+        account_seed: u32,
+        user_info: Pubkey,
+        storage_requested: u64,
+    ) -> Instruction {
+        let wallet_pubkey = self.wallet.pubkey();
 
         let (storage_account, _) = derived_addresses::storage_account(&wallet_pubkey, account_seed);
 
@@ -204,7 +295,7 @@ where
             stake_account,
             token_mint: TOKEN_MINT,
             owner_1: wallet_pubkey,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             owner_1_token_account: owner_ata,
             system_program: system_program::ID,
             token_program: TokenProgram,
@@ -216,21 +307,48 @@ where
             storage: storage_requested,
         };
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
-
-        txn.try_partial_sign(
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        )?;
-
-        let txn_encoded = serialize_and_encode(&txn)?;
+        }
+    }
+}
 
-        Ok(txn_encoded)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_identifier_rules() {
+        assert!(matches!(
+            normalize_identifier("   ", false),
+            Err(Error::InvalidIdentifier(_))
+        ));
+        assert!(matches!(
+            normalize_identifier("a\tb", false),
+            Err(Error::InvalidIdentifier(_))
+        ));
+        assert_eq!(
+            normalize_identifier("  Gallery \n", false).unwrap(),
+            "Gallery"
+        );
+        assert_eq!(normalize_identifier("Gallery", true).unwrap(), "gallery");
+
+        let max = "a".repeat(MAX_IDENTIFIER_SIZE);
+        assert_eq!(normalize_identifier(&max, false).unwrap(), max);
+        assert!(matches!(
+            normalize_identifier(&format!("{}a", max), false),
+            Err(Error::IdentifierTooLong { .. })
+        ));
+
+        // The limit is in bytes: 16 four-byte characters fit, 17 do not.
+        let emoji = "\u{1F5C2}".repeat(16);
+        assert_eq!(normalize_identifier(&emoji, false).unwrap(), emoji);
+        assert!(matches!(
+            normalize_identifier(&"\u{1F5C2}".repeat(17), false),
+            Err(Error::IdentifierTooLong { len: 68, .. })
+        ));
+        assert_eq!(normalize_identifier("Ünïcödé", true).unwrap(), "ünïcödé");
     }
 }
@@ -14,6 +14,12 @@ where
     /// but this must be done before the end of the Solana epoch.
     /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that contains the file.
     /// * `url` - The Shadow Drive url of the file you want to mark for deletion.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ShadowDriveServerError`] if the upload server rejects the deletion.
+    /// - [`Error::SolanaRpcError`] or [`Error::ReqwestError`] on network failures.
+    ///
     /// # Example
     ///
     /// ```
@@ -23,6 +23,14 @@ where
     /// Marking a [`StorageAccount`](crate::models::StorageAccount) for deletion can be undone with `cancel_delete_storage_account`,
     /// but this must be done before the end of the Solana epoch.
     /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that you want to mark for deletion.
+    ///
+    /// # Errors
+    ///
+    /// - [`ShadowDriveServerError`](crate::error::Error::ShadowDriveServerError) if the upload
+    ///   server rejects the transaction.
+    /// - [`SolanaRpcError`](crate::error::Error::SolanaRpcError) or
+    ///   [`ReqwestError`](crate::error::Error::ReqwestError) on network failures.
+    ///
     /// # Example
     ///
     /// ```
@@ -48,7 +56,10 @@ where
         &self,
         storage_account_key: &Pubkey,
     ) -> ShadowDriveResult<ShdwDriveResponse> {
-        let selected_account = self.get_storage_account(storage_account_key).await?;
+        let selected_account = self
+            .get_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
 
         let txn = match selected_account {
             StorageAcct::V1(storage_account) => {
@@ -61,7 +72,7 @@ where
             }
         };
 
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
 
         Ok(ShdwDriveResponse {
             txid: txn_result.to_string(),
@@ -89,7 +100,7 @@ where
         };
 
         let txn = Transaction::new_signed_with_payer(
-            &[instruction],
+            &self.prepend_priority_fee(&[instruction]),
             Some(&self.wallet.pubkey()),
             &[&self.wallet],
             self.rpc_client.get_latest_blockhash().await?,
@@ -103,29 +114,40 @@ where
         storage_account_key: &Pubkey,
         storage_account: StorageAccountV2,
     ) -> ShadowDriveResult<Transaction> {
+        let instruction = self
+            .delete_storage_account_v2_instruction(storage_account_key, storage_account.owner_1);
+
+        let txn = Transaction::new_signed_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&self.wallet.pubkey()),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        );
+
+        Ok(txn)
+    }
+
+    /// Builds the instruction that [`delete_storage_account`](Self::delete_storage_account)
+    /// sends for a v2 account.
+    pub(crate) fn delete_storage_account_v2_instruction(
+        &self,
+        storage_account_key: &Pubkey,
+        owner: Pubkey,
+    ) -> Instruction {
         let accounts = shdw_drive_accounts::RequestDeleteAccountV2 {
             storage_config: *STORAGE_CONFIG_PDA,
             storage_account: *storage_account_key,
-            owner: storage_account.owner_1,
+            owner,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
         };
 
         let args = shdw_drive_instructions::RequestDeleteAccount2 {};
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let txn = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&self.wallet.pubkey()),
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
-
-        Ok(txn)
+        }
     }
 }
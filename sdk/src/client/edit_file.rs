@@ -1,6 +1,7 @@
 use reqwest::multipart::{Form, Part};
 use serde_json::Value;
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use tracing::Instrument;
 
 use super::ShadowDriveClient;
 use crate::{
@@ -14,9 +15,17 @@ where
     T: Signer,
 {
     /// Replace an existing file on the Shadow Drive with the given updated file.
+    /// Fails with [`Error::AccountMarkedForDeletion`] if the account is marked for deletion.
     /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that contains the file.
     /// * `url` - The Shadow Drive url of the file you want to replace.
     /// * `data` - The updated [`ShadowFile`](crate::models::ShadowFile).
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::AccountMarkedForDeletion`] if the account is marked for deletion.
+    /// - [`Error::ShadowDriveServerError`] if the upload server rejects the edit.
+    /// - [`Error::SolanaRpcError`] or [`Error::ReqwestError`] on network failures.
+    ///
     /// # Example
     ///
     /// ```
@@ -47,6 +56,8 @@ where
         storage_account_key: &Pubkey,
         data: ShadowFile,
     ) -> ShadowDriveResult<ShadowEditResponse> {
+        self.writable_storage_account(storage_account_key).await?;
+
         let message_to_sign = edit_message(storage_account_key, data.name(), &data.sha256().await?);
 
         let signature = self
@@ -76,6 +87,7 @@ where
             .post(format!("{}/edit", SHDW_DRIVE_ENDPOINT))
             .multipart(form)
             .send()
+            .instrument(tracing::info_span!("upload"))
             .await?;
 
         if !response.status().is_success() {
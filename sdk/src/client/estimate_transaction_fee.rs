@@ -0,0 +1,80 @@
+use solana_sdk::{message::Message, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::{derived_addresses, models::ShadowDriveResult};
+
+/// A storage operation whose network fee can be estimated with
+/// [`estimate_transaction_fee`](ShadowDriveClient::estimate_transaction_fee).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationKind {
+    CreateStorageAccount,
+    AddStorage,
+    ReduceStorage,
+    MakeStorageImmutable,
+    DeleteStorageAccount,
+    CancelDeleteStorageAccount,
+    ClaimStake,
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Estimates the SOL network fee, in lamports, of the transaction an operation sends,
+    /// including the client's [priority fee](Self::with_priority_fee). Nothing is sent.
+    ///
+    /// The estimate builds the operation's instruction for a v2 account with the same builder the
+    /// operation uses, and asks the RPC for the fee of that message. Solana fees depend only on a
+    /// transaction's signatures and compute budget, so the instruction targets the wallet's first
+    /// storage account with zero-sized arguments, and none of its accounts need to exist.
+    /// It does not include the SHDW cost of storage; see [`estimate_storage_cost`](Self::estimate_storage_cost).
+    /// * `op` - The operation to price.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, OperationKind};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::keypair::read_keypair_file;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let lamports = shdw_drive_client
+    ///     .estimate_transaction_fee(OperationKind::AddStorage)
+    ///     .await?;
+    /// ```
+    pub async fn estimate_transaction_fee(&self, op: OperationKind) -> ShadowDriveResult<u64> {
+        let wallet_pubkey = self.wallet.pubkey();
+        let (storage_account_key, _) = derived_addresses::storage_account(&wallet_pubkey, 0);
+        let instruction = match op {
+            OperationKind::CreateStorageAccount => {
+                let (user_info, _) = derived_addresses::user_info(&wallet_pubkey);
+                self.create_v2_instruction("", 0, user_info, 0)
+            }
+            OperationKind::AddStorage => {
+                self.add_storage_v2_instruction(&storage_account_key, wallet_pubkey, 0)
+            }
+            OperationKind::ReduceStorage => {
+                self.reduce_storage_v2_instruction(&storage_account_key, wallet_pubkey, 0, 0)
+            }
+            OperationKind::MakeStorageImmutable => {
+                self.make_storage_immutable_v2_instruction(&storage_account_key, wallet_pubkey, 0)
+            }
+            OperationKind::DeleteStorageAccount => {
+                self.delete_storage_account_v2_instruction(&storage_account_key, wallet_pubkey)
+            }
+            OperationKind::CancelDeleteStorageAccount => self
+                .cancel_delete_storage_account_v2_instruction(&storage_account_key, wallet_pubkey),
+            OperationKind::ClaimStake => {
+                self.claim_stake_v2_instruction(&storage_account_key, wallet_pubkey)
+            }
+        };
+        let instructions = self.prepend_priority_fee(&[instruction]);
+
+        let blockhash = self.rpc_client.get_latest_blockhash().await?;
+        let message = Message::new_with_blockhash(&instructions, Some(&wallet_pubkey), &blockhash);
+        Ok(self.rpc_client.get_fee_for_message(&message).await?)
+    }
+}
@@ -0,0 +1,52 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::{error::Error, models::ShadowDriveResult};
+
+/// A stored version of a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileVersion {
+    pub version_id: String,
+    /// When the version was stored, as a unix timestamp.
+    pub last_modified: i64,
+}
+
+/// Why file versions can't be listed or fetched.
+const VERSIONING_UNSUPPORTED: &str =
+    "the Shadow Drive gateway does not retain previous versions of edited files";
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Lists the stored versions of a file, newest first.
+    ///
+    /// The Shadow Drive gateway keeps only the current contents of a file:
+    /// [`edit_file`](Self::edit_file) replaces them in place. Until it retains versions this always
+    /// returns [`Error::Unsupported`], rather than presenting the current contents as the only version.
+    /// To keep an audit trail on a mutable account, upload each revision under its own name,
+    /// for example with [`ShadowFile::content_addressed`](crate::models::ShadowFile::content_addressed).
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the file.
+    /// * `filename` - The name of the file.
+    pub async fn list_file_versions(
+        &self,
+        _storage_account_key: &Pubkey,
+        _filename: &str,
+    ) -> ShadowDriveResult<Vec<FileVersion>> {
+        Err(Error::Unsupported(VERSIONING_UNSUPPORTED.to_string()))
+    }
+
+    /// Downloads a version of a file listed by [`list_file_versions`](Self::list_file_versions).
+    /// Always returns [`Error::Unsupported`] for the same reason.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the file.
+    /// * `filename` - The name of the file.
+    /// * `version_id` - The [`FileVersion::version_id`] to download.
+    pub async fn download_file_version(
+        &self,
+        _storage_account_key: &Pubkey,
+        _filename: &str,
+        _version_id: &str,
+    ) -> ShadowDriveResult<Vec<u8>> {
+        Err(Error::Unsupported(VERSIONING_UNSUPPORTED.to_string()))
+    }
+}
@@ -0,0 +1,81 @@
+use shadowy_super_minter::state::{file_type::AccountDeserialize, ShadowySuperMinter};
+use solana_sdk::{clock::Clock, pubkey::Pubkey, signer::Signer, sysvar};
+use std::io;
+
+use super::ShadowDriveClient;
+use crate::{error::Error, models::ShadowDriveResult};
+
+/// Mint progress of a minter, as shown on a mint page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MintStatus {
+    pub items_available: u32,
+    pub items_redeemed: u32,
+    /// Whether the cluster time is within the mint window and items remain.
+    pub is_live: bool,
+    /// Start of the mint window, in Solana cluster time (unix seconds).
+    pub starts_at: i64,
+    /// End of the mint window, in Solana cluster time (unix seconds).
+    pub ends_at: i64,
+}
+
+impl MintStatus {
+    /// Summarizes an already fetched minter at the given Solana cluster time (unix seconds).
+    pub fn new(minter: &ShadowySuperMinter, cluster_time: i64) -> Self {
+        Self {
+            items_available: minter.items_available,
+            items_redeemed: minter.items_redeemed,
+            is_live: (minter.start_time..minter.end_time).contains(&cluster_time)
+                && minter.items_redeemed < minter.items_available,
+            starts_at: minter.start_time,
+            ends_at: minter.end_time,
+        }
+    }
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Fetches a Shadowy Super Minter and the current cluster time, and summarizes the mint's progress.
+    /// * `minter` - The public key of the minter account.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::SolanaRpcError`] if the minter or the clock sysvar cannot be fetched.
+    /// - [`Error::AccountDeserializeError`] if the account is not a minter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let minter = Pubkey::new_unique();
+    /// #
+    /// let status = shdw_drive_client.get_mint_status(&minter).await?;
+    /// println!("{} of {} minted", status.items_redeemed, status.items_available);
+    /// ```
+    pub async fn get_mint_status(&self, minter: &Pubkey) -> ShadowDriveResult<MintStatus> {
+        let minter_data = self.rpc_client.get_account_data(minter).await?;
+        let minter =
+            ShadowySuperMinter::try_deserialize(&mut minter_data.as_slice()).map_err(|e| {
+                Error::AccountDeserializeError(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    e.to_string(),
+                ))
+            })?;
+        let clock_data = self.rpc_client.get_account_data(&sysvar::clock::ID).await?;
+        let clock: Clock = bincode::deserialize(&clock_data).map_err(|e| {
+            Error::AccountDeserializeError(io::Error::new(io::ErrorKind::InvalidData, e))
+        })?;
+        Ok(MintStatus::new(&minter, clock.unix_timestamp))
+    }
+}
@@ -0,0 +1,90 @@
+use futures::{stream, StreamExt, TryStreamExt};
+use reqwest::StatusCode;
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::collections::HashMap;
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::SHDW_DRIVE_OBJECT_PREFIX,
+    error::Error,
+    models::{FileMetadata, ShadowDriveResult},
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Fetches the [`FileMetadata`] of many files at once, with up to `max_concurrency`
+    /// HEAD requests in flight. Files that do not exist are left out of the returned map.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the files.
+    /// * `names` - The names of the files to examine.
+    /// * `max_concurrency` - Maximum number of requests to run at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let names = shdw_drive_client.list_objects(&storage_account_key).await?;
+    /// let metadata = shdw_drive_client
+    ///     .get_object_data_many(&storage_account_key, &names, 16)
+    ///     .await?;
+    /// ```
+    pub async fn get_object_data_many(
+        &self,
+        storage_account_key: &Pubkey,
+        names: &[String],
+        max_concurrency: usize,
+    ) -> ShadowDriveResult<HashMap<String, FileMetadata>> {
+        let entries: Vec<_> = stream::iter(names)
+            .map(|name| async move {
+                let metadata = self.object_metadata(storage_account_key, name).await?;
+                Ok::<_, Error>(metadata.map(|metadata| (name.clone(), metadata)))
+            })
+            .buffer_unordered(max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        Ok(entries.into_iter().flatten().collect())
+    }
+
+    async fn object_metadata(
+        &self,
+        storage_account_key: &Pubkey,
+        name: &str,
+    ) -> ShadowDriveResult<Option<FileMetadata>> {
+        let response = self
+            .http_client
+            .head(format!(
+                "{}/{}/{}",
+                SHDW_DRIVE_OBJECT_PREFIX, storage_account_key, name
+            ))
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(Error::ShadowDriveServerError {
+                status: response.status().as_u16(),
+                message: Value::Null,
+            });
+        }
+
+        FileMetadata::from_headers(response.headers()).map(Some)
+    }
+}
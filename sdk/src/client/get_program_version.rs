@@ -0,0 +1,112 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::{bpf_loader_upgradeable::UpgradeableLoaderState, signer::Signer};
+use tracing::Instrument;
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::{PROGRAM_ADDRESS, STORAGE_CONFIG_PDA},
+    error::Error,
+    models::*,
+    StorageConfig,
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Reads the deployment of the Shadow Drive program and checks that it is compatible with this SDK.
+    /// The program carries no version number, so compatibility is judged by the on-chain
+    /// [`StorageConfig`](crate::StorageConfig): if its discriminator or layout no longer match the
+    /// ones this SDK was built against, instructions built by the SDK are likely to fail and
+    /// [`Error::IncompatibleProgram`] is returned. The returned [`ProgramVersion`] identifies the
+    /// deployment by the slot in which it was last upgraded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::keypair::read_keypair_file;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let program_version = shdw_drive_client
+    ///     .get_program_version()
+    ///     .await?;
+    /// ```
+    pub async fn get_program_version(&self) -> ShadowDriveResult<ProgramVersion> {
+        let program_data = self
+            .rpc_client
+            .get_account_data(&PROGRAM_ADDRESS)
+            .instrument(tracing::info_span!("rpc_account_fetch"))
+            .await?;
+        let programdata_address = match bincode::deserialize(&program_data) {
+            Ok(UpgradeableLoaderState::Program {
+                programdata_address,
+            }) => programdata_address,
+            _ => {
+                return Err(Error::IncompatibleProgram(
+                    "program is not deployed with the upgradeable loader".to_string(),
+                ))
+            }
+        };
+
+        // Only the metadata header is needed, not the program binary.
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: UpgradeableLoaderState::size_of_programdata_metadata(),
+            }),
+            commitment: Some(self.rpc_client.commitment()),
+            min_context_slot: None,
+        };
+        let programdata = self
+            .rpc_client
+            .get_account_with_config(&programdata_address, config)
+            .instrument(tracing::info_span!("rpc_account_fetch"))
+            .await?
+            .value
+            .ok_or_else(|| {
+                Error::IncompatibleProgram("program data account not found".to_string())
+            })?;
+        let (deployed_slot, upgrade_authority) = match bincode::deserialize(&programdata.data) {
+            Ok(UpgradeableLoaderState::ProgramData {
+                slot,
+                upgrade_authority_address,
+            }) => (slot, upgrade_authority_address),
+            _ => {
+                return Err(Error::IncompatibleProgram(
+                    "unexpected program data account contents".to_string(),
+                ))
+            }
+        };
+
+        let storage_config = self
+            .rpc_client
+            .get_account(&STORAGE_CONFIG_PDA)
+            .instrument(tracing::info_span!("rpc_account_fetch"))
+            .await?;
+        if storage_config.owner != PROGRAM_ADDRESS
+            || !storage_config
+                .data
+                .starts_with(&StorageConfig::discriminator())
+        {
+            return Err(Error::IncompatibleProgram(
+                "storage config discriminator does not match".to_string(),
+            ));
+        }
+        StorageConfig::try_deserialize(&mut storage_config.data.as_slice()).map_err(|e| {
+            Error::IncompatibleProgram(format!("storage config layout does not match: {}", e))
+        })?;
+
+        Ok(ProgramVersion {
+            deployed_slot,
+            upgrade_authority,
+        })
+    }
+}
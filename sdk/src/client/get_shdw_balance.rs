@@ -0,0 +1,51 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+use super::ShadowDriveClient;
+use crate::{constants::TOKEN_MINT, error::Error, models::*, units::Shdw};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Returns the SHDW balance held in the associated token account of `owner`.
+    /// * `owner` - The wallet whose balance to fetch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::{keypair::read_keypair_file, Signer};
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let balance = shdw_drive_client
+    ///     .get_shdw_balance(&user_pubkey)
+    ///     .await?;
+    /// ```
+    pub async fn get_shdw_balance(&self, owner: &Pubkey) -> ShadowDriveResult<Shdw> {
+        let owner_ata = get_associated_token_address(owner, &TOKEN_MINT);
+        self.token_account_balance(&owner_ata)
+            .await
+            .map(Shdw::from_shades)
+    }
+
+    /// Returns the raw token amount held by a token account.
+    pub(crate) async fn token_account_balance(
+        &self,
+        token_account: &Pubkey,
+    ) -> ShadowDriveResult<u64> {
+        let balance = self
+            .rpc_client
+            .get_token_account_balance(token_account)
+            .await?;
+        balance
+            .amount
+            .parse::<u64>()
+            .map_err(|_| Error::InvalidTokenBalance(balance.amount))
+    }
+}
@@ -1,22 +1,70 @@
-use anchor_lang::AccountDeserialize;
-use futures::future::join_all;
-use serde_json::json;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use futures::{future, future::join_all, stream, Stream, StreamExt, TryStreamExt};
+use serde_json::{json, Value};
+use shadow_drive_user_staking::instructions::initialize_account::{
+    StorageAccount as StorageAccountV1Data, StorageAccountV2 as StorageAccountV2Data,
+};
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use tracing::Instrument;
 
-use super::ShadowDriveClient;
+use super::{ShadowDriveClient, StorageAccountVersion};
 use crate::{
-    constants::SHDW_DRIVE_ENDPOINT,
+    constants::{PROGRAM_ADDRESS, SHDW_DRIVE_ENDPOINT},
     derived_addresses,
-    models::{storage_acct::StorageAcct, *},
+    error::Error,
+    models::{
+        storage_acct::{StorageAccount, StorageAccountInfo, StorageAccountV2, StorageAcct},
+        *,
+    },
 };
 
+/// Selects which storage accounts [`get_storage_accounts`](ShadowDriveClient::get_storage_accounts) returns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StorageAccountFilter {
+    /// Every account, including those marked for deletion.
+    #[default]
+    All,
+    /// Only accounts that are not marked for deletion.
+    OnlyActive,
+    /// Only accounts that are marked for deletion, e.g. to cancel their deletion.
+    OnlyPendingDeletion,
+}
+
+impl StorageAccountFilter {
+    pub fn matches(&self, account: &StorageAccountInfo) -> bool {
+        match self {
+            StorageAccountFilter::All => true,
+            StorageAccountFilter::OnlyActive => !account.to_be_deleted(),
+            StorageAccountFilter::OnlyPendingDeletion => account.to_be_deleted(),
+        }
+    }
+}
+
+/// Maximum number of accounts the RPC returns from a single `getMultipleAccounts` call.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Maximum number of storage accounts [`get_storage_accounts_stream`](ShadowDriveClient::get_storage_accounts_stream)
+/// fetches at once.
+pub const ACCOUNTS_STREAM_CONCURRENCY: usize = 16;
+
 impl<T> ShadowDriveClient<T>
 where
     T: Signer,
 {
-    /// Returns the [`StorageAccount`](crate::models::StorageAccount) associated with the pubkey provided by a user.
+    /// Returns the [`StorageAccount`](crate::models::StorageAccount) associated with the pubkey provided by a user,
+    /// as a version-agnostic [`StorageAccountInfo`](crate::models::storage_acct::StorageAccountInfo).
+    /// The account is read from the Shadow Drive server, which tags it with its version. If the server
+    /// rejects the request with a non-transient error, the account is read from the chain and decoded as
+    /// v1 or v2 according to its discriminator; v2 accounts read this way report a `current_usage` of 0.
+    /// Transient errors (see [`RetryPolicy`](crate::RetryPolicy)) are returned once retries run out.
     /// * `key` - The public key of the [`StorageAccount`](crate::models::StorageAccount).
     ///
+    /// # Errors
+    ///
+    /// - [`Error::SolanaRpcError`] if both the server and the RPC node fail to return the account.
+    /// - [`Error::InvalidAccountDiscriminator`] or [`Error::AccountDeserializeError`] if the account
+    ///   read from the chain is not a storage account.
+    ///
     /// # Example
     ///
     /// ```
@@ -39,7 +87,48 @@ where
     ///     .await
     ///     .expect("failed to get storage account");
     /// ```
-    pub async fn get_storage_account(&self, key: &Pubkey) -> ShadowDriveResult<StorageAcct> {
+    pub async fn get_storage_account(&self, key: &Pubkey) -> ShadowDriveResult<StorageAccountInfo> {
+        if let Some(account) = self.account_cache.as_ref().and_then(|cache| cache.get(key)) {
+            return Ok(account);
+        }
+
+        let response: ShadowDriveResult<StorageAcct> = self
+            .with_retries(|| {
+                self.storage_account_info(key)
+                    .instrument(tracing::info_span!("rpc_account_fetch"))
+            })
+            .await;
+
+        // If the gateway can't describe the account, decode it from the chain, where the version
+        // is read from the account's discriminator. Transient failures that outlasted the retry
+        // policy are returned instead, since the chain can't report a v2 account's usage.
+        let response = match response {
+            Ok(response) => response,
+            Err(err) if err.is_transient() => return Err(err),
+            Err(err) => {
+                tracing::warn!(
+                    ?err,
+                    %key,
+                    "failed to fetch storage account from the gateway, reading it from the chain"
+                );
+                let data = self
+                    .rpc_client
+                    .get_account_data(key)
+                    .instrument(tracing::info_span!("rpc_account_fetch"))
+                    .await?;
+                storage_account_from_data(key, &data)?
+            }
+        };
+
+        let account = StorageAccountInfo::from(response);
+        if let Some(cache) = &self.account_cache {
+            cache.insert(*key, account.clone());
+        }
+        Ok(account)
+    }
+
+    /// Requests the gateway's view of the storage account at `key`.
+    async fn storage_account_info(&self, key: &Pubkey) -> ShadowDriveResult<StorageAcct> {
         let response = self
             .http_client
             .post(format!("{}/storage-account-info", SHDW_DRIVE_ENDPOINT))
@@ -47,20 +136,82 @@ where
                 "storage_account": key.to_string()
             }))
             .send()
-            .await?
-            .json()
             .await?;
 
-        Ok(response)
+        if !response.status().is_success() {
+            return Err(Error::ShadowDriveServerError {
+                status: response.status().as_u16(),
+                message: response.json::<Value>().await.unwrap_or(Value::Null),
+            });
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Returns the v2 [`StorageAccountV2`] at `key` exactly as stored on-chain, read with
+    /// `getAccountInfo` rather than through the Shadow Drive server's unified view.
+    /// The program does not track usage for v2 accounts, so `current_usage` is 0.
+    /// Fails with [`Error::InvalidAccountDiscriminator`] if the account is not a v2 storage account.
+    /// * `key` - The public key of the storage account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let storage_account = shdw_drive_client
+    ///     .get_storage_account_v2(&storage_account_key)
+    ///     .await?;
+    /// println!("{} bytes reserved", storage_account.reserved_bytes);
+    /// ```
+    pub async fn get_storage_account_v2(
+        &self,
+        key: &Pubkey,
+    ) -> ShadowDriveResult<StorageAccountV2> {
+        let data = self
+            .rpc_client
+            .get_account_data(key)
+            .instrument(tracing::info_span!("rpc_account_fetch"))
+            .await?;
+        storage_account_v2_from_data(key, &data)
+    }
+
+    /// Fetches a storage account ahead of a write, failing with
+    /// [`Error::AccountMarkedForDeletion`] if it is marked for deletion.
+    pub(crate) async fn writable_storage_account(
+        &self,
+        key: &Pubkey,
+    ) -> ShadowDriveResult<StorageAccountInfo> {
+        let account = self.get_storage_account(key).await?;
+        if account.to_be_deleted() {
+            return Err(Error::AccountMarkedForDeletion(*key));
+        }
+        Ok(account)
     }
 
-    /// Returns all [`StorageAccount`]s associated with the public key provided by a user.
+    /// Returns the [`StorageAccount`]s associated with the public key provided by a user.
+    /// Accounts are enumerated from the owner's `UserInfo` counter, without `getProgramAccounts`.
+    /// Accounts the Shadow Drive gateway fails to return are read from the chain with
+    /// `getMultipleAccounts` instead; those report a `current_usage` of 0 for v2 accounts.
     /// * `owner` - The public key that is the owner of all the returned [`StorageAccount`]s.
+    /// * `filter` - Which accounts to return, based on whether they are marked for deletion.
+    /// [`StorageAccountFilter::All`] returns every account.
     ///
     /// # Example
     ///
     /// ```
-    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use shadow_drive_rust::{ShadowDriveClient, StorageAccountFilter};
     /// # use solana_client::rpc_client::RpcClient;
     /// # use solana_sdk::{
     /// # pubkey::Pubkey,
@@ -74,14 +225,15 @@ where
     /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
     /// #
     /// let storage_accounts = shdw_drive_client
-    ///     .get_storage_accounts(&user_pubkey)
+    ///     .get_storage_accounts(&user_pubkey, StorageAccountFilter::All)
     ///     .await
     ///     .expect("failed to get storage account");
     /// ```
     pub async fn get_storage_accounts(
         &self,
         owner: &Pubkey,
-    ) -> ShadowDriveResult<Vec<StorageAcct>> {
+        filter: StorageAccountFilter,
+    ) -> ShadowDriveResult<Vec<StorageAccountInfo>> {
         let (user_info_key, _) = derived_addresses::user_info(owner);
         let user_info = self.rpc_client.get_account_data(&user_info_key).await?;
         let user_info = UserInfo::try_deserialize(&mut user_info.as_slice())?;
@@ -89,21 +241,346 @@ where
         let accounts_to_fetch = (0..user_info.account_counter)
             .map(|account_seed| derived_addresses::storage_account(owner, account_seed).0);
 
-        let accounts = accounts_to_fetch.map(|storage_account_key| async move {
-            self.get_storage_account(&storage_account_key).await
-        });
+        let accounts = accounts_to_fetch
+            .clone()
+            .map(|storage_account_key| async move {
+                self.get_storage_account(&storage_account_key).await
+            });
+
+        let mut accounts = Vec::new();
+        let mut unfetched = Vec::new();
+        for (key, account) in accounts_to_fetch.zip(join_all(accounts).await) {
+            match account {
+                Ok(account) => accounts.push(account),
+                Err(err) => {
+                    tracing::debug!(?err, %key, "failed to fetch storage account from the gateway");
+                    unfetched.push(key);
+                }
+            }
+        }
 
-        let (accounts, errors): (
-            Vec<ShadowDriveResult<StorageAcct>>,
-            Vec<ShadowDriveResult<StorageAcct>>,
-        ) = join_all(accounts)
-            .await
+        // Accounts the gateway could not serve are read from the chain instead.
+        // Closed accounts leave gaps in the counter and are skipped.
+        for keys in unfetched.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let on_chain = self
+                .rpc_client
+                .get_multiple_accounts(keys)
+                .instrument(tracing::info_span!("rpc_account_fetch"))
+                .await?;
+            for (key, account) in keys.iter().zip(on_chain) {
+                let Some(account) = account else { continue };
+                match storage_account_from_data(key, &account.data) {
+                    Ok(account) => accounts.push(StorageAccountInfo::from(account)),
+                    Err(err) => tracing::debug!(?err, %key, "failed to decode storage account"),
+                }
+            }
+        }
+
+        Ok(accounts
             .into_iter()
-            .partition(Result::is_ok);
+            .filter(|account| filter.matches(account))
+            .collect())
+    }
+
+    /// Streams the storage accounts of `owner`, like [`get_storage_accounts`](Self::get_storage_accounts)
+    /// with [`StorageAccountFilter::All`], but yields each account as soon as it and the accounts
+    /// before it are fetched, so callers can render them incrementally. Accounts are yielded in
+    /// creation order, with up to [`ACCOUNTS_STREAM_CONCURRENCY`] fetched at once. Closed accounts are
+    /// skipped; an account that fails to load is yielded as an error without ending the stream.
+    /// * `owner` - The public key that is the owner of all the returned [`StorageAccount`]s.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use futures::StreamExt;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let mut accounts = shdw_drive_client.get_storage_accounts_stream(&user_pubkey);
+    /// while let Some(account) = accounts.next().await {
+    ///     println!("{}", account?.identifier());
+    /// }
+    /// ```
+    #[must_use]
+    pub fn get_storage_accounts_stream<'a>(
+        &'a self,
+        owner: &'a Pubkey,
+    ) -> impl Stream<Item = ShadowDriveResult<StorageAccountInfo>> + 'a {
+        let keys = stream::once(async move {
+            let (user_info_key, _) = derived_addresses::user_info(owner);
+            let user_info = self.rpc_client.get_account_data(&user_info_key).await?;
+            let user_info = UserInfo::try_deserialize(&mut user_info.as_slice())?;
+            Ok::<_, Error>(
+                stream::iter(0..user_info.account_counter).map(move |account_seed| {
+                    Ok::<_, Error>(derived_addresses::storage_account(owner, account_seed).0)
+                }),
+            )
+        })
+        .try_flatten();
+
+        keys.map_ok(move |key| async move { self.get_open_storage_account(&key).await })
+            .try_buffered(ACCOUNTS_STREAM_CONCURRENCY)
+            .filter_map(|account| future::ready(account.transpose()))
+    }
+
+    /// Like [`get_storage_account`](Self::get_storage_account), but returns `None` for a
+    /// closed account instead of an error.
+    async fn get_open_storage_account(
+        &self,
+        key: &Pubkey,
+    ) -> ShadowDriveResult<Option<StorageAccountInfo>> {
+        match self.get_storage_account(key).await {
+            Ok(account) => Ok(Some(account)),
+            Err(err) => {
+                let account = self
+                    .rpc_client
+                    .get_account_with_commitment(key, self.rpc_client.commitment())
+                    .instrument(tracing::info_span!("rpc_account_fetch"))
+                    .await?
+                    .value;
+                match account {
+                    Some(_) => Err(err),
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+
+    /// Returns whether `owner` already has a storage account named `identifier`.
+    /// Storage accounts are addressed by a per-owner counter rather than by name,
+    /// so nothing on-chain prevents two accounts from sharing an identifier.
+    /// Accounts marked for deletion are included. Owners who never created an account have no used identifiers.
+    /// * `owner` - The owner of the storage accounts to check.
+    /// * `identifier` - The storage account name to look for.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let used = shdw_drive_client
+    ///     .is_identifier_used(&user_pubkey, "mysite")
+    ///     .await?;
+    /// ```
+    pub async fn is_identifier_used(
+        &self,
+        owner: &Pubkey,
+        identifier: &str,
+    ) -> ShadowDriveResult<bool> {
+        let (user_info_key, _) = derived_addresses::user_info(owner);
+        let user_info = self
+            .rpc_client
+            .get_account_with_commitment(&user_info_key, self.rpc_client.commitment())
+            .await?
+            .value;
+        if user_info.is_none() {
+            return Ok(false);
+        }
+
+        let accounts = self
+            .get_storage_accounts(owner, StorageAccountFilter::All)
+            .await?;
+        Ok(accounts
+            .iter()
+            .any(|account| account.identifier() == identifier))
+    }
+
+    /// Returns whether a [`StorageAccount`](crate::models::StorageAccount) is initialized at the given address.
+    /// A missing account, or one not owned by the Shadow Drive program, is reported as `false` rather than an error.
+    /// * `key` - The public key of the storage account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let exists = shdw_drive_client
+    ///     .storage_account_exists(&storage_account_key)
+    ///     .await?;
+    /// ```
+    pub async fn storage_account_exists(&self, key: &Pubkey) -> ShadowDriveResult<bool> {
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(key, self.rpc_client.commitment())
+            .instrument(tracing::info_span!("rpc_account_fetch"))
+            .await?
+            .value;
+        Ok(account.map_or(false, |account| {
+            account.owner == PROGRAM_ADDRESS && account_version_from_data(&account.data).is_ok()
+        }))
+    }
+
+    /// Returns the [`StorageAccountVersion`] of the account at `key`.
+    /// The version is determined from the anchor discriminator of the raw account data,
+    /// so this only costs a single `getAccountInfo` call and skips the Shadow Drive server.
+    /// For v1 accounts, the optional second owner is read from the account data as well.
+    /// * `key` - The public key of the [`StorageAccount`](crate::models::StorageAccount).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let version = shdw_drive_client
+    ///     .get_account_version(&storage_account_key)
+    ///     .await?;
+    /// ```
+    pub async fn get_account_version(
+        &self,
+        key: &Pubkey,
+    ) -> ShadowDriveResult<StorageAccountVersion> {
+        let data = self
+            .rpc_client
+            .get_account_data(key)
+            .instrument(tracing::info_span!("rpc_account_fetch"))
+            .await?;
+        account_version_from_data(&data)
+    }
+}
+
+/// Classifies raw storage account data by its anchor discriminator.
+pub(crate) fn account_version_from_data(data: &[u8]) -> ShadowDriveResult<StorageAccountVersion> {
+    if data.len() < 8 {
+        return Err(Error::InvalidAccountDiscriminator);
+    }
+    let discriminator = &data[..8];
+
+    if discriminator == StorageAccountV2Data::discriminator() {
+        Ok(StorageAccountVersion::V2)
+    } else if discriminator == StorageAccountV1Data::discriminator() {
+        let account = StorageAccountV1Data::try_deserialize(&mut &data[..])?;
+        let owner_2 = Some(account.owner_2).filter(|owner_2| *owner_2 != Pubkey::default());
+        Ok(StorageAccountVersion::V1 { owner_2 })
+    } else {
+        Err(Error::InvalidAccountDiscriminator)
+    }
+}
+
+/// Decodes raw on-chain storage account data that must be a v2 account.
+pub(crate) fn storage_account_v2_from_data(
+    key: &Pubkey,
+    data: &[u8],
+) -> ShadowDriveResult<StorageAccountV2> {
+    match storage_account_from_data(key, data)? {
+        StorageAcct::V2(account) => Ok(account),
+        StorageAcct::V1(_) => Err(Error::InvalidAccountDiscriminator),
+    }
+}
+
+/// Decodes raw on-chain storage account data. The program does not track
+/// usage for v2 accounts, so their `current_usage` is reported as 0.
+pub(crate) fn storage_account_from_data(
+    key: &Pubkey,
+    data: &[u8],
+) -> ShadowDriveResult<StorageAcct> {
+    match account_version_from_data(data)? {
+        StorageAccountVersion::V1 { .. } => {
+            let account = StorageAccountV1Data::try_deserialize(&mut &data[..])?;
+            Ok(StorageAcct::V1(StorageAccount {
+                storage_account: *key,
+                reserved_bytes: account.storage,
+                current_usage: account.storage.saturating_sub(account.storage_available),
+                immutable: account.immutable,
+                to_be_deleted: account.to_be_deleted,
+                delete_request_epoch: account.delete_request_epoch,
+                owner_1: account.owner_1,
+                owner_2: account.owner_2,
+                account_counter_seed: account.account_counter_seed,
+                creation_time: account.creation_time,
+                creation_epoch: account.creation_epoch,
+                last_fee_epoch: account.last_fee_epoch,
+                identifier: account.identifier,
+            }))
+        }
+        StorageAccountVersion::V2 => {
+            let account = StorageAccountV2Data::try_deserialize(&mut &data[..])?;
+            Ok(StorageAcct::V2(StorageAccountV2 {
+                storage_account: *key,
+                reserved_bytes: account.storage,
+                current_usage: 0,
+                immutable: account.immutable,
+                to_be_deleted: account.to_be_deleted,
+                delete_request_epoch: account.delete_request_epoch,
+                owner_1: account.owner_1,
+                account_counter_seed: account.account_counter_seed,
+                creation_time: account.creation_time,
+                creation_epoch: account.creation_epoch,
+                last_fee_epoch: account.last_fee_epoch,
+                identifier: account.identifier,
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utilities::{mock_storage_account_v1, MockStorageAccount};
 
-        tracing::debug!(?errors, "encountered errors fetching storage_accounts");
+    #[test]
+    fn v2_account_data_decodes_as_v2() {
+        let key = Pubkey::new_unique();
+        let account = MockStorageAccount::default()
+            .storage(1 << 20)
+            .identifier("v2")
+            .build_v2();
+        let decoded = storage_account_v2_from_data(&key, &account.data).unwrap();
+        assert_eq!(decoded.storage_account, key);
+        assert_eq!(decoded.reserved_bytes, 1 << 20);
+        assert_eq!(decoded.identifier, "v2");
+        assert!(matches!(
+            storage_account_from_data(&key, &account.data).unwrap(),
+            StorageAcct::V2(_)
+        ));
 
-        //unwrap is safe due do the abve partition
-        Ok(accounts.into_iter().map(Result::unwrap).collect())
+        assert!(matches!(
+            storage_account_v2_from_data(&key, &mock_storage_account_v1().data),
+            Err(Error::InvalidAccountDiscriminator)
+        ));
     }
 }
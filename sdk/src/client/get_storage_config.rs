@@ -0,0 +1,110 @@
+use anchor_lang::AccountDeserialize;
+use byte_unit::Byte;
+use solana_sdk::signer::Signer;
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::{BYTES_PER_GIB, STORAGE_CONFIG_PDA},
+    error::Error,
+    models::*,
+    units::Shdw,
+    StorageConfig,
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Returns the on-chain [`StorageConfig`](crate::StorageConfig) of the Shadow Drive program.
+    /// The config holds storage pricing, account size bounds, and admin pubkeys.
+    /// New storage accounts must be between `min_account_size` and `max_account_size` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::keypair::read_keypair_file;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let storage_config = shdw_drive_client
+    ///     .get_storage_config()
+    ///     .await?;
+    /// println!(
+    ///     "Account sizes: {}..={} bytes",
+    ///     storage_config.min_account_size, storage_config.max_account_size
+    /// );
+    /// ```
+    pub async fn get_storage_config(&self) -> ShadowDriveResult<StorageConfig> {
+        let data = self
+            .rpc_client
+            .get_account_data(&STORAGE_CONFIG_PDA)
+            .await?;
+        let storage_config = StorageConfig::try_deserialize(&mut data.as_slice())?;
+        Ok(storage_config)
+    }
+
+    /// Estimates the cost of reserving `size` bytes of storage,
+    /// using the current `shades_per_gib` rate from the on-chain [`StorageConfig`](crate::StorageConfig).
+    /// No transaction is sent.
+    /// * `size` - The amount of storage to price.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::units::parse_storage_size;
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::keypair::read_keypair_file;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let size = parse_storage_size("1GB").expect("invalid size string");
+    /// #
+    /// let cost = shdw_drive_client
+    ///     .estimate_storage_cost(size)
+    ///     .await?;
+    /// println!("Estimated cost: {}", cost);
+    /// ```
+    pub async fn estimate_storage_cost(&self, size: Byte) -> ShadowDriveResult<Shdw> {
+        let size: u64 = size
+            .get_bytes()
+            .try_into()
+            .map_err(|_| Error::InvalidStorage)?;
+        let storage_config = self.get_storage_config().await?;
+        Ok(Shdw::from_shades(storage_cost(
+            size,
+            storage_config.shades_per_gib,
+        )))
+    }
+}
+
+/// Computes the cost in shades of `bytes` of storage at a rate of `shades_per_gib`,
+/// matching the on-chain computation.
+pub fn storage_cost(bytes: u64, shades_per_gib: u64) -> u64 {
+    ((bytes as u128) * (shades_per_gib as u128) / (BYTES_PER_GIB as u128)) as u64
+}
+
+/// Checks a requested storage account size against the bounds of the [`StorageConfig`],
+/// as the program does on-chain when the account is created.
+pub(crate) fn check_account_size(
+    storage_config: &StorageConfig,
+    requested: u64,
+) -> ShadowDriveResult<()> {
+    let (min, max) = (
+        storage_config.min_account_size,
+        storage_config.max_account_size,
+    );
+    if requested < min || requested > max {
+        return Err(Error::SizeOutOfRange {
+            min,
+            max,
+            requested,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use futures::{stream, StreamExt};
+use reqwest::header::CONTENT_TYPE;
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::{collections::HashMap, path::Path};
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::SHDW_DRIVE_OBJECT_PREFIX,
+    error::Error,
+    models::{FileMetadata, ShadowDriveResult, TextObject},
+};
+
+/// Maximum number of downloads [`get_texts`](ShadowDriveClient::get_texts) runs at once.
+const GET_TEXTS_CONCURRENCY: usize = 8;
+
+/// Fragments of the error pages misconfigured CDNs in front of the gateway
+/// serve with a 200 status, matched case-insensitively.
+const GATEWAY_ERROR_SIGNATURES: &[&str] = &[
+    "<title>502 bad gateway</title>",
+    "<title>503 service temporarily unavailable</title>",
+    "<title>504 gateway time-out</title>",
+    "<title>attention required! | cloudflare</title>",
+    "cf-error-details",
+];
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Downloads a file and decodes it as UTF-8 text, along with its [`FileMetadata`].
+    /// Returns [`Error::InvalidUtf8`] if the file is not valid UTF-8; see
+    /// [`get_text_lossy`](Self::get_text_lossy) to replace invalid sequences instead.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the file.
+    /// * `filename` - The name of the file to download.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let text = shdw_drive_client
+    ///     .get_text(&storage_account_key, "index.html")
+    ///     .await?;
+    /// println!("{}", text.content);
+    /// ```
+    pub async fn get_text(
+        &self,
+        storage_account_key: &Pubkey,
+        filename: &str,
+    ) -> ShadowDriveResult<TextObject> {
+        let (data, metadata) = self.get_object(storage_account_key, filename).await?;
+        let content = String::from_utf8(data).map_err(|e| {
+            Error::InvalidUtf8(format!("{} is not valid UTF-8 text: {}", filename, e))
+        })?;
+        Ok(TextObject { content, metadata })
+    }
+
+    /// Like [`get_text`](Self::get_text), but replaces invalid UTF-8 sequences with `U+FFFD` instead of failing.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the file.
+    /// * `filename` - The name of the file to download.
+    pub async fn get_text_lossy(
+        &self,
+        storage_account_key: &Pubkey,
+        filename: &str,
+    ) -> ShadowDriveResult<TextObject> {
+        let (data, metadata) = self.get_object(storage_account_key, filename).await?;
+        let content = String::from_utf8_lossy(&data).into_owned();
+        Ok(TextObject { content, metadata })
+    }
+
+    /// Downloads several text files at once, like [`get_text`](Self::get_text) but
+    /// without metadata. A file that is missing or not valid UTF-8 does not fail the
+    /// whole call: each name maps to its own result.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the files.
+    /// * `names` - The names of the files to download.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let pages = vec![String::from("index.html"), String::from("about.html")];
+    /// let texts = shdw_drive_client
+    ///     .get_texts(&storage_account_key, &pages)
+    ///     .await?;
+    /// ```
+    pub async fn get_texts(
+        &self,
+        storage_account_key: &Pubkey,
+        names: &[String],
+    ) -> ShadowDriveResult<HashMap<String, Result<String, Error>>> {
+        Ok(stream::iter(names)
+            .map(|name| async move {
+                let text = self.get_text(storage_account_key, name).await;
+                (name.clone(), text.map(|text| text.content))
+            })
+            .buffer_unordered(GET_TEXTS_CONCURRENCY)
+            .collect()
+            .await)
+    }
+
+    pub(crate) async fn get_object(
+        &self,
+        storage_account_key: &Pubkey,
+        filename: &str,
+    ) -> ShadowDriveResult<(Vec<u8>, FileMetadata)> {
+        let response = self
+            .http_client
+            .get(format!(
+                "{}/{}/{}",
+                SHDW_DRIVE_OBJECT_PREFIX, storage_account_key, filename
+            ))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Error::ShadowDriveServerError {
+                status: response.status().as_u16(),
+                message: response.json::<Value>().await.unwrap_or(Value::Null),
+            });
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let metadata = FileMetadata::from_headers(response.headers());
+        let data = response.bytes().await?.to_vec();
+        // An error page usually lacks the object headers too, so report it first.
+        check_gateway_error(filename, content_type.as_deref(), &data)?;
+        Ok((data, metadata?))
+    }
+}
+
+/// Returns [`Error::GatewayError`] if a download of `filename` was answered with an
+/// HTML error page instead of the object: either HTML for a file that is not HTML,
+/// or a body matching one of [`GATEWAY_ERROR_SIGNATURES`].
+fn check_gateway_error(
+    filename: &str,
+    content_type: Option<&str>,
+    data: &[u8],
+) -> ShadowDriveResult<()> {
+    let is_html = content_type.map_or(false, |content_type| {
+        content_type
+            .trim_start()
+            .to_ascii_lowercase()
+            .starts_with("text/html")
+    });
+    if !is_html {
+        return Ok(());
+    }
+
+    let expects_html = Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| {
+            ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm")
+        });
+    if !expects_html {
+        return Err(Error::GatewayError(format!(
+            "expected {} but the gateway returned an HTML page",
+            filename
+        )));
+    }
+
+    let body = String::from_utf8_lossy(data).to_ascii_lowercase();
+    match GATEWAY_ERROR_SIGNATURES
+        .iter()
+        .find(|signature| body.contains(*signature))
+    {
+        Some(signature) => Err(Error::GatewayError(format!(
+            "expected {} but the gateway returned an error page matching {:?}",
+            filename, signature
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_gateway_error;
+    use crate::error::Error;
+
+    const ERROR_PAGE: &[u8] = b"<html>\r\n<head><title>502 Bad Gateway</title></head>\r\n\
+        <body><center><h1>502 Bad Gateway</h1></center></body>\r\n</html>";
+
+    #[test]
+    fn html_error_pages_are_gateway_errors() {
+        assert!(matches!(
+            check_gateway_error("0.json", Some("text/html; charset=utf-8"), ERROR_PAGE),
+            Err(Error::GatewayError(_))
+        ));
+        assert!(matches!(
+            check_gateway_error("index.html", Some("text/html"), ERROR_PAGE),
+            Err(Error::GatewayError(_))
+        ));
+        assert!(check_gateway_error("index.html", Some("text/html"), b"<h1>Hello</h1>").is_ok());
+        assert!(check_gateway_error("0.json", Some("application/json"), ERROR_PAGE).is_ok());
+    }
+}
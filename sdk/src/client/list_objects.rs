@@ -1,5 +1,7 @@
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
 use serde_json::{json, Value};
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::path::Path;
 
 use crate::{
     constants::SHDW_DRIVE_ENDPOINT,
@@ -9,6 +11,55 @@ use crate::{
 
 use super::ShadowDriveClient;
 
+/// Maximum number of HEAD requests [`list_objects_with_sizes`](ShadowDriveClient::list_objects_with_sizes)
+/// runs at once.
+pub const LIST_SIZES_CONCURRENCY: usize = 16;
+
+/// Selects a subset of the files in a storage account, for use with
+/// [`list_objects_filtered`](ShadowDriveClient::list_objects_filtered).
+/// Criteria are combined: a file must match all of them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ObjectFilter {
+    prefix: Option<String>,
+    extension: Option<String>,
+}
+
+impl ObjectFilter {
+    /// A filter matching every file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match files whose name starts with `prefix`, e.g. `"images/"`.
+    #[must_use]
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Only match files with the given extension, e.g. `"json"` or `".json"`.
+    /// The comparison is case-insensitive.
+    #[must_use]
+    pub fn extension(mut self, extension: &str) -> Self {
+        self.extension = Some(extension.trim_start_matches('.').to_string());
+        self
+    }
+
+    pub fn matches(&self, file_name: &str) -> bool {
+        let prefix_matches = self
+            .prefix
+            .as_ref()
+            .map_or(true, |prefix| file_name.starts_with(prefix.as_str()));
+        let extension_matches = self.extension.as_ref().map_or(true, |extension| {
+            Path::new(file_name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map_or(false, |ext| ext.eq_ignore_ascii_case(extension))
+        });
+        prefix_matches && extension_matches
+    }
+}
+
 impl<T> ShadowDriveClient<T>
 where
     T: Signer,
@@ -17,6 +68,10 @@ where
     /// The output contains all of the file names as strings.
     /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the files.
     ///
+    /// # Errors
+    ///
+    /// - [`Error::ReqwestError`] on network failures or if the response is not a list of keys.
+    ///
     /// # Example
     ///
     /// ```
@@ -42,6 +97,81 @@ where
         &self,
         storage_account_key: &Pubkey,
     ) -> ShadowDriveResult<Vec<String>> {
+        self.list_objects_response(storage_account_key)
+            .await?
+            .json::<ListObjectsResponse>()
+            .await
+            .map(|response| response.keys)
+            .map_err(Error::from)
+    }
+
+    /// Streams the names of all files associated with a storage account.
+    /// Names are yielded as the listing body arrives, so the full list is never held
+    /// in memory, which matters for accounts with hundreds of thousands of files.
+    /// The stream ends after the first error.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the files.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use futures::StreamExt;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let mut files = shdw_drive_client.list_objects_stream(&storage_account_key);
+    /// while let Some(file) = files.next().await {
+    ///     println!("{}", file?);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn list_objects_stream<'a>(
+        &'a self,
+        storage_account_key: &'a Pubkey,
+    ) -> impl Stream<Item = ShadowDriveResult<String>> + Unpin + 'a {
+        let chunks = stream::once(self.list_objects_response(storage_account_key))
+            .map_ok(|response| response.bytes_stream().map_err(Error::from))
+            .try_flatten();
+
+        // A trailing `None` marks the end of the body, so a truncated listing is reported.
+        let names = chunks
+            .map(Some)
+            .chain(stream::once(future::ready(None)))
+            .scan(KeysParser::default(), |parser, chunk| {
+                if parser.finished {
+                    return future::ready(None);
+                }
+                let names = match chunk {
+                    Some(Ok(chunk)) => parser.push(&chunk),
+                    Some(Err(e)) => Err(e),
+                    None => parser.finish().map(|()| vec![]),
+                };
+                parser.finished |= names.is_err();
+                future::ready(Some(names))
+            })
+            .flat_map(|names| {
+                stream::iter(match names {
+                    Ok(names) => names.into_iter().map(Ok).collect::<Vec<_>>(),
+                    Err(e) => vec![Err(e)],
+                })
+            });
+        Box::pin(names)
+    }
+
+    async fn list_objects_response(
+        &self,
+        storage_account_key: &Pubkey,
+    ) -> ShadowDriveResult<reqwest::Response> {
         let response = self
             .http_client
             .post(format!("{}/list-objects", SHDW_DRIVE_ENDPOINT))
@@ -57,10 +187,227 @@ where
                 message: response.json::<Value>().await?,
             });
         }
-        response
-            .json::<ListObjectsResponse>()
-            .await
-            .map(|response| response.keys)
-            .map_err(Error::from)
+        Ok(response)
+    }
+
+    /// Gets the name and size in bytes of every file in a storage account.
+    /// The list endpoint only returns names, so sizes are read from the `Content-Length`
+    /// of a HEAD request per file, with up to [`LIST_SIZES_CONCURRENCY`] in flight.
+    /// Files are returned in listing order; a file deleted between the listing and its
+    /// HEAD request is left out.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the files.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let files = shdw_drive_client
+    ///     .list_objects_with_sizes(&storage_account_key)
+    ///     .await?;
+    /// let total: u64 = files.iter().map(|(_, size)| size).sum();
+    /// ```
+    pub async fn list_objects_with_sizes(
+        &self,
+        storage_account_key: &Pubkey,
+    ) -> ShadowDriveResult<Vec<(String, u64)>> {
+        let names = self.list_objects(storage_account_key).await?;
+        let mut metadata = self
+            .get_object_data_many(storage_account_key, &names, LIST_SIZES_CONCURRENCY)
+            .await?;
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let content_length = metadata.remove(&name)?.content_length;
+                Some(match content_length {
+                    Some(size) => Ok((name, size)),
+                    None => Err(Error::InvalidObjectMetadata(format!(
+                        "{} has no Content-Length",
+                        name
+                    ))),
+                })
+            })
+            .collect()
+    }
+
+    /// Gets the names of the files in a storage account that match `filter`.
+    /// The Shadow Drive API does not filter listings, so the full list is fetched
+    /// and filtered client-side.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that owns the files.
+    /// * `filter` - The [`ObjectFilter`] files must match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, ObjectFilter, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let metadata_files = shdw_drive_client
+    ///     .list_objects_filtered(&storage_account_key, ObjectFilter::new().extension("json"))
+    ///     .await?;
+    /// ```
+    pub async fn list_objects_filtered(
+        &self,
+        storage_account_key: &Pubkey,
+        filter: ObjectFilter,
+    ) -> ShadowDriveResult<Vec<String>> {
+        let files = self.list_objects(storage_account_key).await?;
+        Ok(files
+            .into_iter()
+            .filter(|file| filter.matches(file))
+            .collect())
+    }
+}
+
+/// Incrementally extracts the file names from a `{"keys": [...]}` list-objects body,
+/// keeping only the bytes of a name that is still incomplete.
+#[derive(Default)]
+struct KeysParser {
+    buffer: Vec<u8>,
+    in_keys: bool,
+    done: bool,
+    finished: bool,
+}
+
+impl KeysParser {
+    /// Appends a chunk of the body and returns the names it completed.
+    fn push(&mut self, chunk: &[u8]) -> ShadowDriveResult<Vec<String>> {
+        if self.done {
+            return Ok(vec![]);
+        }
+        self.buffer.extend_from_slice(chunk);
+
+        let mut pos = 0;
+        if !self.in_keys {
+            let open = find(&self.buffer, b"\"keys\"").and_then(|keys| {
+                let open = self.buffer[keys..].iter().position(|b| *b == b'[')?;
+                Some(keys + open)
+            });
+            match open {
+                Some(open) => pos = open + 1,
+                None => return Ok(vec![]),
+            }
+            self.in_keys = true;
+        }
+
+        let mut names = vec![];
+        while let Some(offset) = self.buffer[pos..]
+            .iter()
+            .position(|b| !b.is_ascii_whitespace() && *b != b',')
+        {
+            pos += offset;
+            match self.buffer[pos] {
+                b']' => {
+                    self.done = true;
+                    self.buffer.clear();
+                    return Ok(names);
+                }
+                b'"' => {
+                    let len = match string_len(&self.buffer[pos..]) {
+                        Some(len) => len,
+                        None => break,
+                    };
+                    let name = serde_json::from_slice::<String>(&self.buffer[pos..pos + len])
+                        .map_err(Error::InvalidJson)?;
+                    names.push(name);
+                    pos += len;
+                }
+                other => {
+                    return Err(Error::InvalidListResponse(format!(
+                        "unexpected {:?} in list of keys",
+                        other as char
+                    )))
+                }
+            }
+        }
+        self.buffer.drain(..pos);
+        Ok(names)
+    }
+
+    /// Checks that the body contained a complete list of keys.
+    fn finish(&mut self) -> ShadowDriveResult<()> {
+        self.finished = true;
+        if self.done {
+            Ok(())
+        } else {
+            Err(Error::InvalidListResponse(
+                "response ended before the list of keys was complete".to_string(),
+            ))
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Length of the JSON string literal at the start of `bytes`, including
+/// both quotes, or `None` if it is not yet terminated.
+fn string_len(bytes: &[u8]) -> Option<usize> {
+    let mut escaped = false;
+    for (i, b) in bytes.iter().enumerate().skip(1) {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeysParser, ObjectFilter};
+
+    #[test]
+    fn filter_combines_prefix_and_extension() {
+        let filter = ObjectFilter::new().prefix("images/").extension(".PNG");
+        assert!(filter.matches("images/0.png"));
+        assert!(!filter.matches("images/0.json"));
+        assert!(!filter.matches("0.png"));
+        assert!(ObjectFilter::new().matches("anything"));
+    }
+
+    #[test]
+    fn keys_parser_handles_names_split_across_chunks() {
+        let body = br#"{"keys": ["a.json", "dir/b \"quoted\".png" ,"c"]}"#;
+        let mut parser = KeysParser::default();
+        let mut names = vec![];
+        for chunk in body.chunks(3) {
+            names.extend(parser.push(chunk).unwrap());
+        }
+        parser.finish().unwrap();
+        assert_eq!(names, vec!["a.json", "dir/b \"quoted\".png", "c"]);
+
+        let mut truncated = KeysParser::default();
+        truncated.push(br#"{"keys":["a","b"#).unwrap();
+        assert!(truncated.finish().is_err());
     }
 }
@@ -0,0 +1,64 @@
+use futures::future::join_all;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::{ShadowDriveClient, StorageAccountFilter};
+use crate::{
+    error::Error,
+    models::{ShadowDriveResult, StorageAccountInfo},
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Finds the storage accounts of `owner` that hold no files and are not immutable.
+    /// Accounts already marked for deletion are skipped. Deleting the returned accounts
+    /// with [`delete_storage_account`](Self::delete_storage_account) reclaims their stake.
+    /// * `owner` - The owner of the storage accounts to inspect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let prunable = shdw_drive_client
+    ///     .list_prunable_accounts(&user_pubkey)
+    ///     .await?;
+    /// for (storage_account_key, _) in prunable {
+    ///     shdw_drive_client.delete_storage_account(&storage_account_key).await?;
+    /// }
+    /// ```
+    pub async fn list_prunable_accounts(
+        &self,
+        owner: &Pubkey,
+    ) -> ShadowDriveResult<Vec<(Pubkey, StorageAccountInfo)>> {
+        let candidates = self
+            .get_storage_accounts(owner, StorageAccountFilter::OnlyActive)
+            .await?
+            .into_iter()
+            .filter(|account| !account.is_immutable());
+
+        let checks = candidates.map(|account| async move {
+            let storage_account_key = account.storage_account();
+            let files = self.list_objects(&storage_account_key).await?;
+            Ok::<_, Error>(files.is_empty().then(|| (storage_account_key, account)))
+        });
+
+        join_all(checks)
+            .await
+            .into_iter()
+            .filter_map(Result::transpose)
+            .collect()
+    }
+}
@@ -15,7 +15,7 @@ use super::ShadowDriveClient;
 use crate::constants::SHDW_DRIVE_ENDPOINT;
 use crate::models::GetBucketSizeResponse;
 use crate::{
-    constants::{EMISSIONS, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT, UPLOADER},
+    constants::{EMISSIONS, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT},
     derived_addresses,
     error::Error,
     models::{
@@ -59,7 +59,10 @@ where
         &self,
         storage_account_key: &Pubkey,
     ) -> ShadowDriveResult<StorageResponse> {
-        let selected_storage_acct = self.get_storage_account(storage_account_key).await?;
+        let selected_storage_acct = self
+            .get_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
 
         let mut bucket_query = HashMap::new();
         bucket_query.insert("storageAccount", storage_account_key.to_string());
@@ -118,7 +121,7 @@ where
             storage_config: *STORAGE_CONFIG_PDA,
             storage_account: *storage_account_key,
             owner: storage_account.owner_1,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             owner_ata,
             stake_account,
             emissions_wallet: emissions_ata,
@@ -139,7 +142,10 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
 
         txn.try_partial_sign(
             &[&self.wallet],
@@ -157,6 +163,36 @@ where
         storage_account: StorageAccountV2,
         storage_used: u64,
     ) -> ShadowDriveResult<String> {
+        let wallet_pubkey = self.wallet.pubkey();
+        let instruction = self.make_storage_immutable_v2_instruction(
+            storage_account_key,
+            storage_account.owner_1,
+            storage_used,
+        );
+
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
+
+        txn.try_partial_sign(
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        )?;
+
+        let txn_encoded = serialize_and_encode(&txn)?;
+
+        Ok(txn_encoded)
+    }
+
+    /// Builds the instruction that [`make_storage_immutable`](Self::make_storage_immutable)
+    /// sends for a v2 account.
+    pub(crate) fn make_storage_immutable_v2_instruction(
+        &self,
+        storage_account_key: &Pubkey,
+        owner: Pubkey,
+        storage_used: u64,
+    ) -> Instruction {
         let wallet_pubkey = self.wallet.pubkey();
         let owner_ata = get_associated_token_address(&wallet_pubkey, &TOKEN_MINT);
         let emissions_ata = get_associated_token_address(&EMISSIONS, &TOKEN_MINT);
@@ -165,8 +201,8 @@ where
         let accounts = shdw_drive_accounts::MakeAccountImmutableV2 {
             storage_config: *STORAGE_CONFIG_PDA,
             storage_account: *storage_account_key,
-            owner: storage_account.owner_1,
-            uploader: UPLOADER,
+            owner,
+            uploader: self.uploader.pubkey,
             owner_ata,
             stake_account,
             emissions_wallet: emissions_ata,
@@ -180,21 +216,10 @@ where
             storage_used: storage_used,
         };
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
-
-        txn.try_partial_sign(
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        )?;
-
-        let txn_encoded = serialize_and_encode(&txn)?;
-
-        Ok(txn_encoded)
+        }
     }
 }
@@ -50,6 +50,68 @@ where
         Ok((step_1_response, step_2_response))
     }
 
+    /// Migrates a v1 [`StorageAccount`](crate::models::StorageAccount) to v2, resuming a partially completed migration.
+    /// If step 1 has already run (the migration helper account exists), only step 2 is sent.
+    /// The returned step 1 response is `None` in that case.
+    ///
+    /// Preconditions: the account must be a v1 account owned by the client's wallet, or a
+    /// migration that has completed step 1 only.
+    ///
+    /// After migration the account lives at the same pubkey as a [`StorageAccountV2`](crate::models::storage_acct::StorageAccountV2):
+    /// identifier, reserved storage, owner and immutability are kept, while v1-only fields
+    /// such as `owner_2` and the separate SHDW payer are dropped.
+    ///
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to be migrated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let (step_1_response, step_2_response) = shdw_drive_client
+    ///     .migrate_storage_account(&storage_account_key)
+    ///     .await?;
+    /// ```
+    pub async fn migrate_storage_account(
+        &self,
+        storage_account_key: &Pubkey,
+    ) -> ShadowDriveResult<(Option<ShdwDriveResponse>, ShdwDriveResponse)> {
+        let step_1_response = if self.is_migration_in_progress(storage_account_key).await? {
+            None
+        } else {
+            Some(self.migrate_step_1(storage_account_key).await?)
+        };
+        let step_2_response = self.migrate_step_2(storage_account_key).await?;
+        Ok((step_1_response, step_2_response))
+    }
+
+    /// Returns whether step 1 of a migration has run for the given account without step 2,
+    /// i.e. whether the migration helper account currently exists.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) being migrated.
+    pub async fn is_migration_in_progress(
+        &self,
+        storage_account_key: &Pubkey,
+    ) -> ShadowDriveResult<bool> {
+        let (migration, _) = derived_addresses::migration_helper(storage_account_key);
+        let account = self
+            .rpc_client
+            .get_account_with_commitment(&migration, self.rpc_client.commitment())
+            .await?;
+        Ok(account.value.is_some())
+    }
+
     /// First transaction step that migrates a v1 [`StorageAccount`](crate::models::StorageAccount) to v2.
     /// Consists of copying the existing account's data into an intermediate account, and deleting the v1 storage account
     pub async fn migrate_step_1(
@@ -74,12 +136,15 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
         txn.try_sign(
             &[&self.wallet],
             self.rpc_client.get_latest_blockhash().await?,
         )?;
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
 
         Ok(ShdwDriveResponse {
             txid: txn_result.to_string(),
@@ -109,12 +174,15 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
         txn.try_sign(
             &[&self.wallet],
             self.rpc_client.get_latest_blockhash().await?,
         )?;
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
 
         Ok(ShdwDriveResponse {
             txid: txn_result.to_string(),
@@ -0,0 +1,368 @@
+use ignore::WalkBuilder;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+};
+
+use super::ShadowDriveClient;
+use crate::{constants::SHDW_DRIVE_OBJECT_PREFIX, error::Error, models::*};
+
+/// Maximum number of new files sent in a single [`store_files`](ShadowDriveClient::store_files) request.
+const MIRROR_UPLOAD_BATCH_SIZE: usize = 5;
+
+/// Name of the files listing, in gitignore syntax, the paths left out when mirroring a directory.
+/// Like `.gitignore` files, one applies to the directory it is in and everything below it.
+pub const SHADOW_IGNORE_FILENAME: &str = ".shadowignore";
+
+/// Which local files [`plan_mirror_with`](ShadowDriveClient::plan_mirror_with) considers.
+/// Paths matched by a [`SHADOW_IGNORE_FILENAME`] file are always left out.
+#[derive(Clone, Debug, Default)]
+pub struct MirrorOptions {
+    /// Also leave out paths matched by `.gitignore` files, whether or not the directory is in a git repository.
+    pub respect_gitignore: bool,
+}
+
+/// The set of changes required to make a storage account mirror a local directory.
+/// Object names are paths relative to the mirrored directory, joined with `/`.
+#[derive(Clone, Debug, Default)]
+pub struct MirrorPlan {
+    /// Local files with no remote counterpart.
+    pub new_files: Vec<(String, PathBuf)>,
+    /// Local files whose contents differ from the remote object of the same name.
+    pub changed_files: Vec<(String, PathBuf)>,
+    /// Local files identical to the remote object of the same name.
+    pub unchanged_files: Vec<String>,
+    /// Remote objects that are not present in the local directory.
+    pub deletions: Vec<String>,
+}
+
+/// Outcome of applying a [`MirrorPlan`].
+#[derive(Clone, Debug, Default)]
+pub struct MirrorResponse {
+    /// Number of new files uploaded.
+    pub uploaded: usize,
+    /// Number of existing files replaced with the local version.
+    pub edited: usize,
+    /// Number of files that were already up to date.
+    pub unchanged: usize,
+    /// Names of the remote objects that were marked for deletion.
+    pub deleted: Vec<String>,
+    /// Errors reported by the upload server for individual files.
+    pub upload_errors: Vec<UploadError>,
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Compares a local directory with the contents of a storage account and returns
+    /// the uploads and deletions needed to make the account an exact mirror of the directory.
+    /// No changes are made. Files are considered changed when their SHA-256 digest differs
+    /// from that of the remote object. Paths matched by a [`SHADOW_IGNORE_FILENAME`] file
+    /// are left out; see [`plan_mirror_with`](Self::plan_mirror_with) to also honor `.gitignore`.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to compare against.
+    /// * `dir` - The local directory to mirror.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let plan = shdw_drive_client
+    ///     .plan_mirror(&storage_account_key, "./site")
+    ///     .await?;
+    /// println!("Objects to delete: {:?}", plan.deletions);
+    /// ```
+    pub async fn plan_mirror<P: AsRef<Path>>(
+        &self,
+        storage_account_key: &Pubkey,
+        dir: P,
+    ) -> ShadowDriveResult<MirrorPlan> {
+        self.plan_mirror_with(storage_account_key, dir, &MirrorOptions::default())
+            .await
+    }
+
+    /// Like [`plan_mirror`](Self::plan_mirror), but with control over which local files are considered.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to compare against.
+    /// * `dir` - The local directory to mirror.
+    /// * `options` - Which ignore files to honor.
+    pub async fn plan_mirror_with<P: AsRef<Path>>(
+        &self,
+        storage_account_key: &Pubkey,
+        dir: P,
+        options: &MirrorOptions,
+    ) -> ShadowDriveResult<MirrorPlan> {
+        let local_files = local_files(dir.as_ref(), options)?;
+        let remote_files: HashSet<String> = self
+            .list_objects(storage_account_key)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut plan = MirrorPlan::default();
+        let mut local_names = HashSet::new();
+
+        for (name, path) in local_files {
+            local_names.insert(name.clone());
+            if !remote_files.contains(&name) {
+                plan.new_files.push((name, path));
+                continue;
+            }
+
+            let file = ShadowFile::file(name.clone(), &path);
+            let local_size = path.metadata().map_err(Error::FileSystemError)?.len();
+            let local_hash = file.sha256_digest().await?;
+            if self
+                .remote_object_matches(storage_account_key, &name, local_size, &local_hash)
+                .await?
+            {
+                plan.unchanged_files.push(name);
+            } else {
+                plan.changed_files.push((name, path));
+            }
+        }
+
+        plan.deletions = remote_files
+            .into_iter()
+            .filter(|name| !local_names.contains(name))
+            .collect();
+        plan.deletions.sort();
+
+        Ok(plan)
+    }
+
+    /// Carries out a [`MirrorPlan`] produced by [`plan_mirror`](Self::plan_mirror):
+    /// uploads new files, replaces changed files and marks extra remote objects for deletion.
+    /// Inspect `plan.deletions` before calling this if the deletion set needs confirmation.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to modify.
+    /// * `plan` - The changes to apply.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// # let plan = shdw_drive_client.plan_mirror(&storage_account_key, "./site").await?;
+    /// #
+    /// let mirror_response = shdw_drive_client
+    ///     .apply_mirror_plan(&storage_account_key, plan)
+    ///     .await?;
+    /// ```
+    pub async fn apply_mirror_plan(
+        &self,
+        storage_account_key: &Pubkey,
+        plan: MirrorPlan,
+    ) -> ShadowDriveResult<MirrorResponse> {
+        let mut response = MirrorResponse {
+            unchanged: plan.unchanged_files.len(),
+            ..Default::default()
+        };
+
+        for chunk in plan.new_files.chunks(MIRROR_UPLOAD_BATCH_SIZE) {
+            let files = chunk
+                .iter()
+                .map(|(name, path)| ShadowFile::file(name.clone(), path))
+                .collect();
+            let upload = self.store_files(storage_account_key, files).await?;
+            response.uploaded += chunk.len().saturating_sub(upload.upload_errors.len());
+            response.upload_errors.extend(upload.upload_errors);
+        }
+
+        for (name, path) in plan.changed_files {
+            self.edit_file(storage_account_key, ShadowFile::file(name, path))
+                .await?;
+            response.edited += 1;
+        }
+
+        for name in plan.deletions {
+            let url = format!(
+                "{}/{}/{}",
+                SHDW_DRIVE_OBJECT_PREFIX, storage_account_key, name
+            );
+            self.delete_file(storage_account_key, url).await?;
+            response.deleted.push(name);
+        }
+
+        Ok(response)
+    }
+
+    /// Makes a storage account an exact mirror of a local directory: new and changed files
+    /// are uploaded, and remote objects not present locally are marked for deletion.
+    /// This is [`plan_mirror`](Self::plan_mirror) followed by [`apply_mirror_plan`](Self::apply_mirror_plan);
+    /// use those directly to review the deletion set first.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to modify.
+    /// * `dir` - The local directory to mirror.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let mirror_response = shdw_drive_client
+    ///     .mirror_directory(&storage_account_key, "./site")
+    ///     .await?;
+    /// ```
+    pub async fn mirror_directory<P: AsRef<Path>>(
+        &self,
+        storage_account_key: &Pubkey,
+        dir: P,
+    ) -> ShadowDriveResult<MirrorResponse> {
+        let plan = self.plan_mirror(storage_account_key, dir).await?;
+        self.apply_mirror_plan(storage_account_key, plan).await
+    }
+
+    /// Fetches a remote object and checks it against a local file's size and SHA-256 digest.
+    /// The body is only downloaded when the sizes match.
+    pub(crate) async fn remote_object_matches(
+        &self,
+        storage_account_key: &Pubkey,
+        name: &str,
+        local_size: u64,
+        local_hash: &[u8; 32],
+    ) -> ShadowDriveResult<bool> {
+        let response = self
+            .http_client
+            .get(format!(
+                "{}/{}/{}",
+                SHDW_DRIVE_OBJECT_PREFIX, storage_account_key, name
+            ))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Error::ShadowDriveServerError {
+                status: response.status().as_u16(),
+                message: response.json::<Value>().await.unwrap_or(Value::Null),
+            });
+        }
+
+        if matches!(response.content_length(), Some(len) if len != local_size) {
+            return Ok(false);
+        }
+
+        let remote_hash = Sha256::digest(&response.bytes().await?);
+        Ok(remote_hash.as_slice() == local_hash)
+    }
+}
+
+/// Recursively collects the files under `dir` that are not ignored according to `options`,
+/// named by their `/`-separated path relative to `dir`. Ignore files themselves are left out.
+fn local_files(dir: &Path, options: &MirrorOptions) -> ShadowDriveResult<Vec<(String, PathBuf)>> {
+    let walk = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .git_ignore(options.respect_gitignore)
+        .require_git(false)
+        .add_custom_ignore_filename(SHADOW_IGNORE_FILENAME)
+        .follow_links(true)
+        .build();
+
+    let mut files = vec![];
+    for entry in walk {
+        let entry = entry.map_err(|e| {
+            let message = e.to_string();
+            Error::FileSystemError(
+                e.into_io_error()
+                    .unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, message)),
+            )
+        })?;
+        let path = entry.into_path();
+        let is_ignore_file = path.file_name().map_or(false, |name| {
+            name == SHADOW_IGNORE_FILENAME || (options.respect_gitignore && name == ".gitignore")
+        });
+        if path.is_dir() || is_ignore_file {
+            continue;
+        }
+        let name = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.push((name, path));
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_files_skips_ignored_paths() {
+        let dir = std::env::temp_dir().join(format!("shadow-mirror-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for file in [
+            "index.html",
+            "app.js",
+            "app.js.map",
+            "node_modules/lib/index.js",
+            "drafts/post.html",
+        ] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x").unwrap();
+        }
+        std::fs::write(dir.join(SHADOW_IGNORE_FILENAME), "node_modules/\n*.map\n").unwrap();
+        std::fs::write(dir.join(".gitignore"), "drafts/\n").unwrap();
+
+        let names = |options| {
+            local_files(&dir, &options)
+                .unwrap()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(MirrorOptions::default()),
+            [".gitignore", "app.js", "drafts/post.html", "index.html"]
+        );
+        assert_eq!(
+            names(MirrorOptions {
+                respect_gitignore: true
+            }),
+            ["app.js", "index.html"]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
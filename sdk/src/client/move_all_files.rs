@@ -0,0 +1,148 @@
+use futures::TryStreamExt;
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::time::Duration;
+
+use super::{store_files::single_upload_location, ShadowDriveClient};
+use crate::{
+    constants::SHDW_DRIVE_OBJECT_PREFIX,
+    error::{Error, FileError},
+    models::*,
+};
+
+/// How long [`move_all_files`](ShadowDriveClient::move_all_files) waits for each copy to be
+/// served by the gateway before verifying it.
+const COPY_PROPAGATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Outcome of [`move_all_files`](ShadowDriveClient::move_all_files).
+#[derive(Debug, Default)]
+pub struct MoveFilesResponse {
+    /// Files whose copy in the destination account was verified.
+    /// Their source objects were deleted if `delete_source` was set.
+    pub moved: Vec<String>,
+    /// Files that could not be copied, whose copy did not match the source,
+    /// or whose source object could not be deleted. A source object is never deleted
+    /// unless its copy was verified.
+    pub failed: Vec<FileError>,
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Copies every object of one storage account into another, for example before
+    /// decommissioning the source account. Each object is streamed from the gateway into a new upload,
+    /// then fetched back from the destination and compared by SHA-256 with what was read from the source.
+    /// Only once the copy is verified is the source object deleted, and only if `delete_source` is set.
+    /// Failures are reported per file in [`MoveFilesResponse::failed`] and do not stop the remaining files.
+    /// * `from` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to move files out of.
+    /// * `to` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to move files into.
+    /// * `delete_source` - Whether to delete each source object after its copy is verified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (old_account, _) = storage_account(&user_pubkey, 0);
+    /// # let (new_account, _) = storage_account(&user_pubkey, 1);
+    /// #
+    /// let move_response = shdw_drive_client
+    ///     .move_all_files(&old_account, &new_account, true)
+    ///     .await?;
+    /// for failure in move_response.failed {
+    ///     println!("{} was not moved: {}", failure.file, failure.error);
+    /// }
+    /// ```
+    pub async fn move_all_files(
+        &self,
+        from: &Pubkey,
+        to: &Pubkey,
+        delete_source: bool,
+    ) -> ShadowDriveResult<MoveFilesResponse> {
+        let mut response = MoveFilesResponse::default();
+
+        for name in self.list_objects(from).await? {
+            if let Err(e) = self.copy_and_verify(from, to, &name).await {
+                response.failed.push(FileError {
+                    file: name,
+                    error: format!("copy failed: {:?}", e),
+                });
+                continue;
+            }
+
+            if delete_source {
+                let url = format!("{}/{}/{}", SHDW_DRIVE_OBJECT_PREFIX, from, name);
+                if let Err(e) = self.delete_file(from, url).await {
+                    response.failed.push(FileError {
+                        file: name,
+                        error: format!("copy verified, but source was not deleted: {:?}", e),
+                    });
+                    continue;
+                }
+            }
+            response.moved.push(name);
+        }
+
+        Ok(response)
+    }
+
+    /// Streams an object from one storage account into another and checks that the
+    /// destination serves the same size and SHA-256 digest as was read from the source.
+    async fn copy_and_verify(
+        &self,
+        from: &Pubkey,
+        to: &Pubkey,
+        name: &str,
+    ) -> ShadowDriveResult<()> {
+        let response = self
+            .http_client
+            .get(format!("{}/{}/{}", SHDW_DRIVE_OBJECT_PREFIX, from, name))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(Error::ShadowDriveServerError {
+                status: response.status().as_u16(),
+                message: response.json::<Value>().await.unwrap_or(Value::Null),
+            });
+        }
+
+        let size = response.content_length().ok_or_else(|| {
+            Error::InvalidObjectMetadata(format!("{} has no Content-Length", name))
+        })?;
+        let body = response
+            .bytes_stream()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e));
+        let file = ShadowFile::from_stream(name.to_string(), Box::pin(body), size);
+
+        // Clones of a streamed file share its hasher, so the digest of what was
+        // uploaded is available from `file` once the upload has consumed the stream.
+        let upload = self
+            .store_files_and_await(to, vec![file.clone()], COPY_PROPAGATION_TIMEOUT)
+            .await?;
+        single_upload_location(upload)?;
+        let source_hash = file.sha256_digest().await?;
+
+        if !self
+            .remote_object_matches(to, name, size, &source_hash)
+            .await?
+        {
+            return Err(Error::FileValidationError(vec![FileError {
+                file: name.to_string(),
+                error: "copy does not match the source object".to_string(),
+            }]));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::StatusCode;
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::SHDW_DRIVE_OBJECT_PREFIX,
+    error::Error,
+    models::{ShadowDriveResult, ShadowFile},
+    object_store::ObjectStore,
+};
+
+/// Buckets are storage accounts. [`put`](ObjectStore::put) uploads new objects with
+/// [`store_files`](ShadowDriveClient::store_files) and replaces existing ones with
+/// [`edit_file`](ShadowDriveClient::edit_file), so it fails on immutable accounts if the object exists.
+#[async_trait]
+impl<T> ObjectStore for ShadowDriveClient<T>
+where
+    T: Signer + Send + Sync,
+{
+    type Bucket = Pubkey;
+    type Error = Error;
+
+    async fn put(&self, bucket: &Pubkey, name: &str, data: Bytes) -> ShadowDriveResult<String> {
+        let file = ShadowFile::bytes(name.to_string(), data);
+        if self.exists(bucket, name).await? {
+            return Ok(self.edit_file(bucket, file).await?.finalized_location);
+        }
+        let response = self.store_files(bucket, vec![file]).await?;
+        match response.finalized_locations.into_iter().next() {
+            Some(location) => Ok(location),
+            None => Err(Error::ShadowDriveServerError {
+                status: 200,
+                message: Value::String(response.message),
+            }),
+        }
+    }
+
+    async fn get(&self, bucket: &Pubkey, name: &str) -> ShadowDriveResult<Bytes> {
+        let (data, _) = self.get_object(bucket, name).await?;
+        Ok(data.into())
+    }
+
+    async fn delete(&self, bucket: &Pubkey, name: &str) -> ShadowDriveResult<()> {
+        self.delete_file(bucket, object_url(bucket, name)).await?;
+        Ok(())
+    }
+
+    async fn list(&self, bucket: &Pubkey) -> ShadowDriveResult<Vec<String>> {
+        self.list_objects(bucket).await
+    }
+
+    async fn exists(&self, bucket: &Pubkey, name: &str) -> ShadowDriveResult<bool> {
+        let response = self
+            .http_client
+            .head(object_url(bucket, name))
+            .send()
+            .await?;
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND => Ok(false),
+            status => Err(Error::ShadowDriveServerError {
+                status: status.as_u16(),
+                message: Value::Null,
+            }),
+        }
+    }
+}
+
+fn object_url(storage_account_key: &Pubkey, name: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        SHDW_DRIVE_OBJECT_PREFIX, storage_account_key, name
+    )
+}
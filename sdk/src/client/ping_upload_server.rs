@@ -0,0 +1,34 @@
+use solana_sdk::signer::Signer;
+use std::time::{Duration, Instant};
+
+use super::ShadowDriveClient;
+use crate::{constants::SHDW_DRIVE_ENDPOINT, models::ShadowDriveResult};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Measures the round-trip time of a minimal request to the upload server,
+    /// independently of the Solana RPC. Any HTTP response counts as reachable;
+    /// only connection failures and timeouts return an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::ShadowDriveClient;
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signer::keypair::read_keypair_file;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// #
+    /// let rtt = shdw_drive_client.ping_upload_server().await?;
+    /// println!("upload server responded in {:?}", rtt);
+    /// ```
+    pub async fn ping_upload_server(&self) -> ShadowDriveResult<Duration> {
+        let start = Instant::now();
+        self.http_client.head(SHDW_DRIVE_ENDPOINT).send().await?;
+        Ok(start.elapsed())
+    }
+}
@@ -62,12 +62,15 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
         txn.try_sign(
             &[&self.wallet],
             self.rpc_client.get_latest_blockhash().await?,
         )?;
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
 
         Ok(ShdwDriveResponse {
             txid: txn_result.to_string(),
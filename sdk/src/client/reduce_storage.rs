@@ -15,7 +15,7 @@ use spl_token::ID as TokenProgramID;
 use super::ShadowDriveClient;
 use crate::constants::SHDW_DRIVE_ENDPOINT;
 use crate::{
-    constants::{EMISSIONS, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT, UPLOADER},
+    constants::{EMISSIONS, PROGRAM_ADDRESS, STORAGE_CONFIG_PDA, TOKEN_MINT},
     derived_addresses,
     error::Error,
     models::{
@@ -34,12 +34,18 @@ where
     /// * `size` - The amount of storage you want to remove.
     /// E.g if you have an existing [`StorageAccount`](crate::models::StorageAccount) with 3MB of storage
     /// but you want 2MB total, `size` should equal 1MB.
-    /// When specifying size, only KB, MB, and GB storage units are currently supported.
+    /// Sizes built with [`parse_storage_size`](crate::units::parse_storage_size) treat KB, MB and GB as binary units, matching on-chain GiB pricing.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidStorage`] if `size` does not fit in a `u64`.
+    /// - [`Error::ShadowDriveServerError`] if the upload server rejects the transaction.
+    /// - [`Error::SolanaRpcError`] or [`Error::ReqwestError`] on network failures.
     ///
     /// # Example
     ///
     /// ```
-    /// # use byte_unit::Byte;
+    /// # use shadow_drive_rust::units::parse_storage_size;
     /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
     /// # use solana_client::rpc_client::RpcClient;
     /// # use solana_sdk::{
@@ -53,7 +59,7 @@ where
     /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
     /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
     /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
-    /// # let reduced_bytes = Byte::from_str("1MB").expect("invalid byte string");
+    /// # let reduced_bytes = parse_storage_size("1MB").expect("invalid size string");
     /// #
     /// let reduce_storage_response = shdw_drive_client
     ///     .reduce_storage(&storage_account_key, reduced_bytes)
@@ -69,7 +75,10 @@ where
             .try_into()
             .map_err(|_| Error::InvalidStorage)?;
 
-        let selected_storage_acct = self.get_storage_account(storage_account_key).await?;
+        let selected_storage_acct = self
+            .get_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
         let mut bucket_query = HashMap::new();
         bucket_query.insert("storageAccount", storage_account_key.to_string());
         let response = self
@@ -138,7 +147,7 @@ where
             owner: storage_account.owner_1,
             owner_ata,
             stake_account,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             emissions_wallet: emeissions_ata,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
@@ -156,7 +165,10 @@ where
             data: args.data(),
         };
 
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
         txn.try_partial_sign(
             &[&self.wallet],
             self.rpc_client.get_latest_blockhash().await?,
@@ -174,6 +186,36 @@ where
         size_as_bytes: u64,
         storage_used: u64,
     ) -> ShadowDriveResult<String> {
+        let wallet_pubkey = self.wallet.pubkey();
+        let instruction = self.reduce_storage_v2_instruction(
+            storage_account_key,
+            storage_account.owner_1,
+            size_as_bytes,
+            storage_used,
+        );
+
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
+        txn.try_partial_sign(
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        )?;
+
+        let txn_encoded = serialize_and_encode(&txn)?;
+
+        Ok(txn_encoded)
+    }
+
+    /// Builds the instruction that [`reduce_storage`](Self::reduce_storage) sends for a v2 account.
+    pub(crate) fn reduce_storage_v2_instruction(
+        &self,
+        storage_account_key: &Pubkey,
+        owner: Pubkey,
+        size_as_bytes: u64,
+        storage_used: u64,
+    ) -> Instruction {
         let wallet_pubkey = self.wallet.pubkey();
         let (unstake_account, _) = derived_addresses::unstake_account(storage_account_key);
         let (unstake_info, _) = derived_addresses::unstake_info(storage_account_key);
@@ -188,10 +230,10 @@ where
             storage_account: *storage_account_key,
             unstake_info,
             unstake_account,
-            owner: storage_account.owner_1,
+            owner,
             owner_ata,
             stake_account,
-            uploader: UPLOADER,
+            uploader: self.uploader.pubkey,
             emissions_wallet: emeissions_ata,
             token_mint: TOKEN_MINT,
             system_program: system_program::ID,
@@ -203,20 +245,10 @@ where
             storage_used: storage_used,
         };
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
-        txn.try_partial_sign(
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        )?;
-
-        let txn_encoded = serialize_and_encode(&txn)?;
-
-        Ok(txn_encoded)
+        }
     }
 }
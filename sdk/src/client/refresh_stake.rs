@@ -23,6 +23,7 @@ where
 {
     ///  Allows user to refresh stake account, and unmarks deletion.
     /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) that you want to top up stake for.
+    /// * `amount` - Optional amount of SHDW, in shades, to transfer into the stake account in the same transaction before refreshing.
     /// # Example
     ///
     /// ```
@@ -41,38 +42,53 @@ where
     /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
     /// #
     /// let refresh_stake = shdw_drive_client
-    ///     .refresh_stake(&storage_account_key)
+    ///     .refresh_stake(&storage_account_key, None)
     ///     .await?;
     /// ```
     pub async fn refresh_stake(
         &self,
         storage_account_key: &Pubkey,
+        amount: Option<u64>,
     ) -> ShadowDriveResult<ShdwDriveResponse> {
-        let selected_account = self.get_storage_account(storage_account_key).await?;
+        let selected_account = self
+            .get_storage_account(storage_account_key)
+            .await?
+            .into_versioned();
 
-        let txn = match selected_account {
+        let refresh_instruction = match selected_account {
             StorageAcct::V1(storage_account) => {
                 self.refresh_stake_v1(storage_account_key, storage_account)
-                    .await?
             }
             StorageAcct::V2(storage_account) => {
                 self.refresh_stake_v2(storage_account_key, storage_account)
-                    .await?
             }
         };
 
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
+        let mut instructions = vec![];
+        if let Some(amount) = amount {
+            instructions.push(self.top_up_instruction(storage_account_key, amount));
+        }
+        instructions.push(refresh_instruction);
+
+        let txn = Transaction::new_signed_with_payer(
+            &self.prepend_priority_fee(&instructions),
+            Some(&self.wallet.pubkey()),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash().await?,
+        );
+
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
 
         Ok(ShdwDriveResponse {
             txid: txn_result.to_string(),
         })
     }
 
-    async fn refresh_stake_v1(
+    fn refresh_stake_v1(
         &self,
         storage_account_key: &Pubkey,
         storage_account: StorageAccount,
-    ) -> ShadowDriveResult<Transaction> {
+    ) -> Instruction {
         let wallet_pubkey = self.wallet.pubkey();
         let owner_ata = get_associated_token_address(&wallet_pubkey, &TOKEN_MINT);
         let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
@@ -89,27 +105,18 @@ where
 
         let args = shdw_drive_instructions::RefreshStake {};
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let txn = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&wallet_pubkey),
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
-
-        Ok(txn)
+        }
     }
 
-    async fn refresh_stake_v2(
+    fn refresh_stake_v2(
         &self,
         storage_account_key: &Pubkey,
         storage_account: StorageAccountV2,
-    ) -> ShadowDriveResult<Transaction> {
+    ) -> Instruction {
         let wallet_pubkey = self.wallet.pubkey();
         let owner_ata = get_associated_token_address(&wallet_pubkey, &TOKEN_MINT);
         let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
@@ -127,19 +134,10 @@ where
 
         let args = shdw_drive_instructions::RefreshStake2 {};
 
-        let instruction = Instruction {
+        Instruction {
             program_id: PROGRAM_ADDRESS,
             accounts: accounts.to_account_metas(None),
             data: args.data(),
-        };
-
-        let txn = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&wallet_pubkey),
-            &[&self.wallet],
-            self.rpc_client.get_latest_blockhash().await?,
-        );
-
-        Ok(txn)
+        }
     }
 }
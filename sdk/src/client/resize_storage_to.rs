@@ -0,0 +1,128 @@
+use byte_unit::Byte;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::{
+    error::Error,
+    models::{ShadowDriveResult, StorageResponse},
+};
+
+/// The change needed to bring a storage account to its target size, in bytes.
+#[derive(Debug, PartialEq, Eq)]
+enum Resize {
+    Add(u64),
+    Reduce(u64),
+    Unchanged,
+}
+
+/// Works out how to go from `current` to `target` bytes of storage, given `used` bytes are stored.
+fn resize_delta(current: u64, used: u64, target: u64) -> ShadowDriveResult<Resize> {
+    if target < used {
+        return Err(Error::SizeBelowUsage {
+            used,
+            requested: target,
+        });
+    }
+    Ok(match target.cmp(&current) {
+        std::cmp::Ordering::Greater => Resize::Add(target - current),
+        std::cmp::Ordering::Less => Resize::Reduce(current - target),
+        std::cmp::Ordering::Equal => Resize::Unchanged,
+    })
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Resizes a storage account to a total of `target` bytes of storage, adding or reducing storage
+    /// as needed, so callers don't have to compute the difference from the current size themselves.
+    /// Returns `None` without sending a transaction if the account already has `target` bytes.
+    /// Immutable accounts are grown with [`add_immutable_storage`](Self::add_immutable_storage).
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to resize.
+    /// * `target` - The total amount of storage the account should have.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::SizeBelowUsage`] if `target` is less than the bytes currently stored.
+    /// - [`Error::InvalidStorage`] if `target` does not fit in a `u64`.
+    /// - Any error of [`add_storage`](Self::add_storage), [`add_immutable_storage`](Self::add_immutable_storage)
+    ///   or [`reduce_storage`](Self::reduce_storage), whichever is used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::units::parse_storage_size;
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// # let target = parse_storage_size("5GB").expect("invalid size string");
+    /// #
+    /// let resize_response = shdw_drive_client
+    ///     .resize_storage_to(&storage_account_key, target)
+    ///     .await?;
+    /// ```
+    pub async fn resize_storage_to(
+        &self,
+        storage_account_key: &Pubkey,
+        target: Byte,
+    ) -> ShadowDriveResult<Option<StorageResponse>> {
+        let target: u64 = target
+            .get_bytes()
+            .try_into()
+            .map_err(|_| Error::InvalidStorage)?;
+
+        let account = self.get_storage_account(storage_account_key).await?;
+        let used = self
+            .get_storage_account_size(&storage_account_key.to_string())
+            .await?
+            .storage_used;
+
+        match resize_delta(account.storage(), used, target)? {
+            Resize::Add(bytes) if account.is_immutable() => self
+                .add_immutable_storage(storage_account_key, Byte::from_bytes(bytes.into()))
+                .await
+                .map(Some),
+            Resize::Add(bytes) => self
+                .add_storage(storage_account_key, Byte::from_bytes(bytes.into()))
+                .await
+                .map(Some),
+            Resize::Reduce(bytes) => self
+                .reduce_storage(storage_account_key, Byte::from_bytes(bytes.into()))
+                .await
+                .map(Some),
+            Resize::Unchanged => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize_delta_picks_operation_and_rejects_targets_below_usage() {
+        assert_eq!(resize_delta(3_000, 500, 5_000).unwrap(), Resize::Add(2_000));
+        assert_eq!(
+            resize_delta(3_000, 500, 1_000).unwrap(),
+            Resize::Reduce(2_000)
+        );
+        assert_eq!(resize_delta(3_000, 500, 3_000).unwrap(), Resize::Unchanged);
+        assert!(matches!(
+            resize_delta(3_000, 500, 499),
+            Err(Error::SizeBelowUsage {
+                used: 500,
+                requested: 499
+            })
+        ));
+    }
+}
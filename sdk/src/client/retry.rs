@@ -0,0 +1,93 @@
+use solana_sdk::signer::Signer;
+use std::{future::Future, time::Duration};
+
+use super::ShadowDriveClient;
+use crate::{error::Error, metrics, models::ShadowDriveResult};
+
+/// How the client retries Shadow Drive gateway requests that fail with a
+/// transient error: a connection failure, a timeout, a 429, or a 5xx response.
+/// Retry `n` waits `base_delay * 2^(n - 1)`. Each retry is logged at `warn`
+/// level with target `shadow_drive_sdk::client::retry`, and counted in
+/// `shadow_drive_retries_total` with the `metrics` feature.
+///
+/// Applies to uploads with [`store_files`](ShadowDriveClient::store_files) and to
+/// [`get_storage_account`](ShadowDriveClient::get_storage_account). Transactions are never resent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt. Defaults to 0, i.e. no retries.
+    pub max_retries: u32,
+    /// Delay before the first retry. Defaults to 500ms.
+    pub base_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn delay(&self, retry: u32) -> Duration {
+        self.base_delay.saturating_mul(1 << retry.min(16))
+    }
+}
+
+impl Error {
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            Error::ReqwestError(e) => e.is_connect() || e.is_timeout(),
+            Error::ShadowDriveServerError { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Sets how requests to the Shadow Drive gateway are retried. See [`RetryPolicy`].
+    ///
+    /// # Example
+    /// ```
+    /// use shadow_drive_rust::RetryPolicy;
+    /// use solana_sdk::signer::keypair::Keypair;
+    /// use std::time::Duration;
+    ///
+    /// let wallet = Keypair::generate();
+    /// let shdw_drive = ShadowDriveClient::new(wallet, "https://ssc-dao.genesysgo.net")
+    ///     .with_retry_policy(RetryPolicy {
+    ///         max_retries: 3,
+    ///         base_delay: Duration::from_secs(1),
+    ///     });
+    /// ```
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Runs `request` until it succeeds, fails with a non-transient error, or runs out of retries.
+    pub(crate) async fn with_retries<F, Fut, R>(&self, mut request: F) -> ShadowDriveResult<R>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ShadowDriveResult<R>>,
+    {
+        let mut retry = 0;
+        loop {
+            match request().await {
+                Err(err) if retry < self.retry_policy.max_retries && err.is_transient() => {
+                    let delay = self.retry_policy.delay(retry);
+                    retry += 1;
+                    tracing::warn!(retry, ?delay, ?err, "retrying request");
+                    metrics::record_retry();
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::constants::SHDW_DRIVE_OBJECT_PREFIX;
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Returns the base URL under which the files of a storage account are served.
+    /// A file's public URL is this base followed by `/` and the file name.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let url = shdw_drive_client.storage_account_url(&storage_account_key);
+    /// println!("gallery: {}/index.html", url);
+    /// ```
+    pub fn storage_account_url(&self, storage_account_key: &Pubkey) -> String {
+        format!("{}/{}", SHDW_DRIVE_OBJECT_PREFIX, storage_account_key)
+    }
+
+    /// Renders the [`storage_account_url`](Self::storage_account_url) of a storage account
+    /// as a QR code, encoded as a PNG image.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let png = shdw_drive_client.storage_account_qr(&storage_account_key);
+    /// std::fs::write("share.png", png)?;
+    /// ```
+    #[cfg(feature = "qr")]
+    pub fn storage_account_qr(&self, storage_account_key: &Pubkey) -> Vec<u8> {
+        use image::{DynamicImage, ImageOutputFormat, Luma};
+        use qrcode::QrCode;
+        use std::io::Cursor;
+
+        // The URL is a fixed prefix plus a base58 pubkey, far below QR capacity,
+        // and encoding a PNG into memory has no I/O that could fail.
+        let code = QrCode::new(self.storage_account_url(storage_account_key))
+            .expect("storage account URL fits in a QR code");
+        let image = DynamicImage::ImageLuma8(code.render::<Luma<u8>>().build());
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+            .expect("PNG encoding into memory does not fail");
+        png
+    }
+}
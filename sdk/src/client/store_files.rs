@@ -1,11 +1,97 @@
+use futures::{future, stream, Future, Stream, StreamExt};
 use itertools::Itertools;
-use reqwest::multipart::{Form, Part};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, AUTHORIZATION, ETAG, PROXY_AUTHORIZATION},
+    multipart::{Form, Part},
+};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::{sync::Arc, time::Instant};
+use tokio::sync::{OnceCell, Semaphore};
+use tracing::Instrument;
 
 use super::ShadowDriveClient;
-use crate::{constants::SHDW_DRIVE_ENDPOINT, error::Error, models::*};
+use crate::{
+    constants::{SHDW_DRIVE_ENDPOINT, SHDW_DRIVE_OBJECT_PREFIX},
+    error::{Error, FileError},
+    metrics,
+    models::{check_file_size, payload::Payload, *},
+};
+
+/// Granularity of the [`UploadLimits::max_inflight_bytes`] budget, so the
+/// semaphore's permit counts stay within `u32` for any supported file size.
+const INFLIGHT_PERMIT_BYTES: u64 = 1024;
+
+/// Limits on concurrent uploads, set with [`with_upload_limits`](ShadowDriveClient::with_upload_limits).
+///
+/// Both limits apply at once: an upload starts only when fewer than `max_concurrency` uploads are
+/// running *and* its file fits in the remaining `max_inflight_bytes` budget. With many small files
+/// `max_concurrency` is usually the binding limit; with large files `max_inflight_bytes` is.
+/// A file larger than the whole budget waits until no other upload is in flight and then runs alone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UploadLimits {
+    /// Maximum number of files uploading at once. Defaults to 5.
+    pub max_concurrency: usize,
+    /// Maximum total size, in bytes, of the files uploading at once. Unlimited by default.
+    pub max_inflight_bytes: Option<u64>,
+    /// Stop at the first failed upload: the failing result is yielded last, uploads still in
+    /// flight are cancelled and files not yet started are skipped. Defaults to `false`, which
+    /// uploads every file regardless of earlier failures.
+    pub abort_on_first_error: bool,
+}
+
+impl Default for UploadLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrency: 5,
+            max_inflight_bytes: None,
+            abort_on_first_error: false,
+        }
+    }
+}
+
+/// Header values for debug logging, with credentials replaced by `<redacted>`.
+fn redacted_headers(headers: &HeaderMap) -> Vec<(&str, &str)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == AUTHORIZATION || name == PROXY_AUTHORIZATION {
+                "<redacted>"
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            (name.as_str(), value)
+        })
+        .collect()
+}
+
+/// Header carrying a file's hex-encoded SHA-256 on its multipart part, as a hint for a gateway
+/// that deduplicates by content. The file's body is always sent regardless.
+const CONTENT_HASH_HEADER: &str = "x-content-sha256";
+
+/// Part headers hinting the gateway at a file's contents ahead of its body.
+fn dedup_hint(digest: &[u8; 32]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&hex::encode(digest)) {
+        headers.insert(CONTENT_HASH_HEADER, value);
+    }
+    headers
+}
+
+/// Parses a SHA-256 reported by the gateway, either as bare hex or as an `ETag`, which may be
+/// quoted, weak (`W/`) or prefixed with `sha256:`. Returns `None` for anything else, e.g. an MD5 `ETag`.
+fn parse_reported_sha256(reported: &str) -> Option<[u8; 32]> {
+    let reported = reported.trim();
+    let reported = reported
+        .strip_prefix("W/")
+        .unwrap_or(reported)
+        .trim_matches('"');
+    let reported = reported.strip_prefix("sha256:").unwrap_or(reported);
+    let mut digest = [0u8; 32];
+    hex::decode_to_slice(reported, &mut digest).ok()?;
+    Some(digest)
+}
 
 fn upload_message(storage_account_key: &Pubkey, filename_hash: &str) -> String {
     format!(
@@ -18,10 +104,63 @@ impl<T> ShadowDriveClient<T>
 where
     T: Signer,
 {
+    /// Uploads files to a storage account in a single request.
+    /// Every file must have between 1 byte and [`FILE_SIZE_LIMIT`](crate::constants::FILE_SIZE_LIMIT)
+    /// bytes of contents: empty files are rejected with [`Error::EmptyFile`] and larger ones with
+    /// [`Error::FileTooLarge`], before anything is sent.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::EmptyFile`], [`Error::FileTooLarge`] or [`Error::FileValidationError`] if a file
+    ///   is rejected before upload.
+    /// - [`Error::ShadowDriveServerError`] if the upload server rejects the request.
+    /// - [`Error::ReqwestError`] or [`Error::SolanaRpcError`] on network failures.
     pub async fn store_files(
         &self,
         storage_account_key: &Pubkey,
         data: Vec<ShadowFile>,
+    ) -> ShadowDriveResult<ShadowUploadResponse> {
+        self.store_files_once_checked(storage_account_key, data, &OnceCell::new())
+            .await
+    }
+
+    /// [`store_files`](Self::store_files), but checks that the storage account is writable only
+    /// until the check first succeeds through `account_checked`, so the uploads of a batch
+    /// sharing it fetch the account once.
+    async fn store_files_once_checked(
+        &self,
+        storage_account_key: &Pubkey,
+        data: Vec<ShadowFile>,
+        account_checked: &OnceCell<()>,
+    ) -> ShadowDriveResult<ShadowUploadResponse> {
+        // Reject empty and oversized files up front, rather than after the request has been
+        // signed and earlier files in the batch have been read.
+        for file in &data {
+            check_file_size(file.name(), file.content_len().await?)?;
+        }
+        account_checked
+            .get_or_try_init(|| async {
+                self.writable_storage_account(storage_account_key)
+                    .await
+                    .map(drop)
+            })
+            .await?;
+
+        // Reader-backed files can only be read once, so their uploads are never retried.
+        if data
+            .iter()
+            .any(|file| matches!(file.data, Payload::Stream(_)))
+        {
+            return self.upload_files(storage_account_key, data).await;
+        }
+        self.with_retries(|| self.upload_files(storage_account_key, data.clone()))
+            .await
+    }
+
+    async fn upload_files(
+        &self,
+        storage_account_key: &Pubkey,
+        data: Vec<ShadowFile>,
     ) -> ShadowDriveResult<ShadowUploadResponse> {
         let filenames = data.iter().map(ShadowFile::name).join(",");
 
@@ -37,9 +176,30 @@ where
             .to_string();
 
         let mut form = Form::new();
+        let mut file_hashes = Vec::with_capacity(data.len());
+        let file_count = data.len() as u64;
+        let mut file_sizes = Vec::with_capacity(data.len());
+        let mut streamed_files = vec![];
 
         for file in data {
-            form = form.part("file", file.into_form_part().await?)
+            let digest = match &file.data {
+                // Reader-backed files are hashed as they stream, so their digest is read after upload.
+                Payload::Stream(stream) => {
+                    streamed_files.push((file.name.clone(), stream.clone()));
+                    None
+                }
+                _ => {
+                    let digest = file.sha256_digest().await?;
+                    file_hashes.push((file.name.clone(), digest));
+                    Some(digest)
+                }
+            };
+            file_sizes.push((file.name.clone(), file.content_len().await?));
+            let mut part = file.into_form_part().await?;
+            if let Some(digest) = digest {
+                part = part.headers(dedup_hint(&digest));
+            }
+            form = form.part("file", part)
         }
 
         form = form
@@ -51,22 +211,375 @@ where
             )
             .part("fileNames", Part::text(filenames));
 
-        let response = self
+        let request = self
             .http_client
             .post(format!("{}/upload", SHDW_DRIVE_ENDPOINT))
             .multipart(form)
-            .send()
-            .await?;
+            .build()?;
+        tracing::debug!(
+            method = %request.method(),
+            url = %request.url(),
+            headers = ?redacted_headers(request.headers()),
+            file_count,
+            file_bytes = file_sizes.iter().map(|(_, size)| size).sum::<u64>(),
+            "sending upload request"
+        );
+
+        let start = Instant::now();
+        let response = self
+            .http_client
+            .execute(request)
+            .instrument(tracing::info_span!("upload"))
+            .await
+            .map_err(|e| {
+                metrics::record_upload(0, file_count, 0, start.elapsed());
+                e
+            })?;
 
         if !response.status().is_success() {
+            metrics::record_upload(0, file_count, 0, start.elapsed());
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            tracing::debug!(status, %body, "upload request failed");
             return Err(Error::ShadowDriveServerError {
-                status: response.status().as_u16(),
-                message: response.json::<Value>().await.unwrap_or(Value::Null),
+                status,
+                message: serde_json::from_str(&body).unwrap_or(Value::Null),
             });
         }
 
-        let response = response.json::<ShadowUploadResponse>().await?;
+        // The ETag of a multi-file upload can't describe any one file.
+        let etag = match file_count {
+            1 => response
+                .headers()
+                .get(ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map(str::to_string),
+            _ => None,
+        };
+        let mut response = response.json::<ShadowUploadResponse>().await?;
+        file_hashes.extend(
+            streamed_files
+                .into_iter()
+                .filter_map(|(name, stream)| Some((name, stream.digest()?))),
+        );
+
+        // "Already exists" only means the name is taken, so it's a dedup hit only if the
+        // stored object has the contents that were sent.
+        let mut upload_errors = Vec::with_capacity(response.upload_errors.len());
+        for upload_error in std::mem::take(&mut response.upload_errors) {
+            let is_duplicate = upload_error
+                .error
+                .to_ascii_lowercase()
+                .contains("already exists")
+                && self
+                    .stored_contents_match(
+                        storage_account_key,
+                        &upload_error.file,
+                        &file_sizes,
+                        &file_hashes,
+                    )
+                    .await;
+            if is_duplicate {
+                response.finalized_locations.push(format!(
+                    "{}/{}/{}",
+                    SHDW_DRIVE_OBJECT_PREFIX, storage_account_key, upload_error.file
+                ));
+                response.deduplicated_files.push(upload_error.file);
+            } else {
+                upload_errors.push(upload_error);
+            }
+        }
+        response.upload_errors = upload_errors;
+        response.file_hashes = file_hashes;
+        response.integrity_verified = response
+            .file_hashes
+            .iter()
+            .filter(|(name, _)| !response.upload_errors.iter().any(|e| &e.file == name))
+            .map(|(name, digest)| {
+                let reported = response
+                    .hashes
+                    .get(name)
+                    .map(String::as_str)
+                    .or(etag.as_deref());
+                let verified = reported
+                    .and_then(parse_reported_sha256)
+                    .map(|reported| reported == *digest);
+                (name.clone(), verified)
+            })
+            .collect();
+        for name in response.integrity_mismatches() {
+            tracing::warn!(
+                file = name,
+                "gateway hash does not match the uploaded contents"
+            );
+        }
+
+        let failed = response.upload_errors.len() as u64;
+        response.file_sizes = file_sizes
+            .into_iter()
+            .filter(|(name, _)| !response.upload_errors.iter().any(|e| &e.file == name))
+            .collect();
+        response.bytes_uploaded = response.file_sizes.iter().map(|(_, size)| size).sum();
+        metrics::record_upload(
+            file_count.saturating_sub(failed),
+            failed,
+            response.bytes_uploaded,
+            start.elapsed(),
+        );
 
         Ok(response)
     }
+
+    /// Whether the stored object `name` has the size and SHA-256 of the file sent under that name.
+    /// Anything that prevents checking, including a failed request, counts as a mismatch.
+    async fn stored_contents_match(
+        &self,
+        storage_account_key: &Pubkey,
+        name: &str,
+        file_sizes: &[(String, u64)],
+        file_hashes: &[(String, [u8; 32])],
+    ) -> bool {
+        let size = file_sizes.iter().find(|(file, _)| file == name);
+        let hash = file_hashes.iter().find(|(file, _)| file == name);
+        match size.zip(hash) {
+            Some(((_, size), (_, hash))) => self
+                .remote_object_matches(storage_account_key, name, *size, hash)
+                .await
+                .unwrap_or(false),
+            None => false,
+        }
+    }
+
+    /// Uploads files like [`store_files`](Self::store_files), but yields a [`FileUploadResult`]
+    /// for each file as soon as it finalizes instead of waiting for the whole batch.
+    /// Each file is sent in its own upload request, several at a time within the client's
+    /// [`UploadLimits`], so results arrive in completion order rather than the order of `data`.
+    /// The storage account is fetched once for the whole batch, not once per file.
+    /// With [`UploadLimits::abort_on_first_error`] set, the stream ends right after the first
+    /// failed result, so everything it yielded before that succeeded.
+    /// Call [`warm_up`](Self::warm_up) first to keep connection setup out of the first uploads.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to upload to.
+    /// * `data` - The files to upload.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use futures::StreamExt;
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account, models::ShadowFile};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// # let files = vec![ShadowFile::file("example.png".to_string(), "./example.png")];
+    /// #
+    /// let mut uploads = shdw_drive_client.store_files_stream(&storage_account_key, files);
+    /// while let Some(upload) = uploads.next().await {
+    ///     println!("{}: {:?}", upload.file_name, upload.result);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn store_files_stream<'a>(
+        &'a self,
+        storage_account_key: &'a Pubkey,
+        data: Vec<ShadowFile>,
+    ) -> impl Stream<Item = FileUploadResult> + 'a {
+        let account_checked = Arc::new(OnceCell::new());
+        self.upload_limits.fan_out(data, move |file| {
+            let account_checked = account_checked.clone();
+            async move {
+                self.store_files_once_checked(storage_account_key, vec![file], &account_checked)
+                    .await
+                    .and_then(single_upload_location)
+            }
+        })
+    }
+}
+
+impl UploadLimits {
+    /// Runs `upload` on each file within these limits, yielding each result as it completes.
+    /// This is the concurrency behind [`store_files_stream`](ShadowDriveClient::store_files_stream).
+    pub(crate) fn fan_out<'a, F, Fut>(
+        &self,
+        data: Vec<ShadowFile>,
+        upload: F,
+    ) -> impl Stream<Item = FileUploadResult> + 'a
+    where
+        F: Fn(ShadowFile) -> Fut + 'a,
+        Fut: Future<Output = ShadowDriveResult<String>> + 'a,
+    {
+        let max_inflight_permits = self
+            .max_inflight_bytes
+            .map(|bytes| (bytes / INFLIGHT_PERMIT_BYTES).clamp(1, u32::MAX as u64) as u32);
+        let inflight =
+            max_inflight_permits.map(|permits| Arc::new(Semaphore::new(permits as usize)));
+        let abort_on_first_error = self.abort_on_first_error;
+        let upload = Arc::new(upload);
+
+        stream::iter(data)
+            .map(move |file| {
+                let inflight = inflight.clone();
+                let upload = upload.clone();
+                async move {
+                    let file_name = file.name.clone();
+                    let result =
+                        upload_within_budget(file, inflight.zip(max_inflight_permits), &*upload)
+                            .await;
+                    FileUploadResult { file_name, result }
+                }
+            })
+            .buffer_unordered(self.max_concurrency.max(1))
+            // Ending the stream drops the buffered uploads, which cancels those still in flight.
+            .scan(false, move |failed, upload| {
+                if *failed {
+                    return future::ready(None);
+                }
+                *failed = abort_on_first_error && upload.result.is_err();
+                future::ready(Some(upload))
+            })
+    }
+
+    /// Uploads files to an [`ObjectStore`](crate::object_store::ObjectStore) within these limits,
+    /// exactly as [`store_files_stream`](ShadowDriveClient::store_files_stream) does to Shadow Drive,
+    /// e.g. to test or benchmark the fan-out against a
+    /// [`MemoryObjectStore`](crate::test_utilities::MemoryObjectStore).
+    /// Reader-backed files are not supported and fail with [`Error::Unsupported`].
+    #[cfg(any(test, feature = "test-utilities"))]
+    pub fn upload_to<'a, S>(
+        &self,
+        store: &'a S,
+        bucket: &'a S::Bucket,
+        data: Vec<ShadowFile>,
+    ) -> impl Stream<Item = FileUploadResult> + 'a
+    where
+        S: crate::object_store::ObjectStore<Error = Error>,
+    {
+        self.fan_out(data, move |file| async move {
+            let contents = match file.data {
+                Payload::Bytes(data) => data,
+                Payload::File(path) => tokio::fs::read(path).await?.into(),
+                Payload::Stream(_) => {
+                    return Err(Error::Unsupported(format!(
+                        "{} is reader-backed and can't be stored in an object store",
+                        file.name
+                    )))
+                }
+            };
+            store.put(bucket, &file.name, contents).await
+        })
+    }
+}
+
+/// Runs `upload` on a single file, first reserving its size from the in-flight byte budget if there is one.
+async fn upload_within_budget<F, Fut>(
+    file: ShadowFile,
+    inflight: Option<(Arc<Semaphore>, u32)>,
+    upload: &F,
+) -> ShadowDriveResult<String>
+where
+    F: Fn(ShadowFile) -> Fut,
+    Fut: Future<Output = ShadowDriveResult<String>>,
+{
+    let _permit = match inflight {
+        Some((inflight, max_permits)) => {
+            let len = file.content_len().await?;
+            let permits = (len / INFLIGHT_PERMIT_BYTES).clamp(1, max_permits as u64) as u32;
+            inflight.acquire_many_owned(permits).await.ok()
+        }
+        None => None,
+    };
+    upload(file).await
+}
+
+/// Extracts the finalized location of the only file in a single-file upload.
+pub(crate) fn single_upload_location(response: ShadowUploadResponse) -> ShadowDriveResult<String> {
+    if let Some(location) = response.finalized_locations.into_iter().next() {
+        return Ok(location);
+    }
+    if !response.upload_errors.is_empty() {
+        return Err(Error::FileValidationError(
+            response
+                .upload_errors
+                .into_iter()
+                .map(|e| FileError {
+                    file: e.file,
+                    error: e.error,
+                })
+                .collect(),
+        ));
+    }
+    Err(Error::ShadowDriveServerError {
+        status: 200,
+        message: Value::String(response.message),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signer::keypair::Keypair;
+
+    #[tokio::test]
+    async fn store_files_rejects_empty_file_before_sending() {
+        // Nothing listens here: the upload must fail before any request is made.
+        let client = ShadowDriveClient::new(Keypair::new(), "http://127.0.0.1:1");
+        let files = vec![
+            ShadowFile::bytes("index.html".to_string(), "<html></html>"),
+            ShadowFile::bytes(".keep".to_string(), vec![]),
+        ];
+        let result = client.store_files(&Pubkey::new_unique(), files).await;
+        assert!(matches!(result, Err(Error::EmptyFile(name)) if name == ".keep"));
+    }
+
+    #[tokio::test]
+    async fn upload_limits_fan_out_stores_every_file() {
+        use crate::{object_store::ObjectStore, test_utilities::MemoryObjectStore};
+
+        let store = MemoryObjectStore::new();
+        let bucket = Pubkey::new_unique();
+        let limits = UploadLimits {
+            max_concurrency: 2,
+            max_inflight_bytes: Some(INFLIGHT_PERMIT_BYTES),
+            abort_on_first_error: false,
+        };
+        let files = (0..5)
+            .map(|i| ShadowFile::bytes(format!("{}.json", i), vec![0u8; 2048]))
+            .collect();
+        let results: Vec<_> = limits.upload_to(&store, &bucket, files).collect().await;
+
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().all(|upload| upload.result.is_ok()));
+        assert_eq!(store.list(&bucket).await.unwrap().len(), 5);
+    }
+
+    #[test]
+    fn parses_sha256_hashes_and_etags() {
+        let digest: [u8; 32] = Sha256::digest(b"hello").into();
+        let hex = hex::encode(digest);
+        for reported in [
+            hex.clone(),
+            hex.to_uppercase(),
+            format!("\"{}\"", hex),
+            format!("W/\"sha256:{}\"", hex),
+        ] {
+            assert_eq!(
+                parse_reported_sha256(&reported),
+                Some(digest),
+                "{}",
+                reported
+            );
+        }
+        // An MD5 ETag can't be compared against a SHA-256.
+        assert_eq!(
+            parse_reported_sha256("\"5d41402abc4b2a76b9719d911017c592\""),
+            None
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::time::{Duration, Instant};
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::ESTIMATED_PROPAGATION_DELAY,
+    error::Error,
+    models::{ShadowDriveResult, ShadowFile, ShadowUploadResponse},
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Uploads files like [`store_files`](Self::store_files), then waits until every
+    /// finalized location can be fetched from the gateway. Locations are polled with HEAD
+    /// requests, first after [`ESTIMATED_PROPAGATION_DELAY`], then at doubling intervals.
+    /// Returns [`Error::ObjectNotAvailable`] with the locations still missing if `timeout` elapses.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to upload to.
+    /// * `data` - The files to upload.
+    /// * `timeout` - How long to wait for the uploaded files to become available.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// # use std::time::Duration;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// # let files = vec![];
+    /// #
+    /// let upload_response = shdw_drive_client
+    ///     .store_files_and_await(&storage_account_key, files, Duration::from_secs(60))
+    ///     .await?;
+    /// ```
+    pub async fn store_files_and_await(
+        &self,
+        storage_account_key: &Pubkey,
+        data: Vec<ShadowFile>,
+        timeout: Duration,
+    ) -> ShadowDriveResult<ShadowUploadResponse> {
+        let response = self.store_files(storage_account_key, data).await?;
+
+        let deadline = Instant::now() + timeout;
+        let mut pending = response.finalized_locations.clone();
+        let mut poll_interval = ESTIMATED_PROPAGATION_DELAY;
+        while !pending.is_empty() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::ObjectNotAvailable(pending));
+            }
+            tokio::time::sleep(poll_interval.min(remaining)).await;
+            poll_interval *= 2;
+
+            let mut still_pending = Vec::with_capacity(pending.len());
+            for location in pending {
+                let available = self
+                    .http_client
+                    .head(&location)
+                    .send()
+                    .await
+                    .map_or(false, |response| response.status().is_success());
+                if !available {
+                    still_pending.push(location);
+                }
+            }
+            pending = still_pending;
+        }
+
+        Ok(response)
+    }
+}
@@ -0,0 +1,201 @@
+use futures::future::join_all;
+use reqwest::{header, StatusCode};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::collections::HashSet;
+
+use super::ShadowDriveClient;
+use crate::{
+    constants::SHDW_DRIVE_OBJECT_PREFIX,
+    error::Error,
+    models::{payload::Payload, *},
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Uploads only the files whose contents differ from the stored object of the same name,
+    /// as in [`store_files`](Self::store_files). Unchanged files are listed in
+    /// [`ShadowUploadResponse::skipped_files`] rather than uploaded.
+    ///
+    /// Each file is checked with a conditional `HEAD` request carrying `If-None-Match` with the
+    /// file's MD5, which the gateway uses as the ETag of single-part objects; a `304 Not Modified`
+    /// means the file is unchanged. If the gateway ignores the condition, the returned ETag is
+    /// compared instead, and when the ETag is not a plain MD5 the object is downloaded and its
+    /// SHA-256 compared. Reader-backed files cannot be hashed ahead of time and are always uploaded.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to upload to.
+    /// * `data` - The files to upload if changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account, models::ShadowFile};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// # let files = vec![ShadowFile::file("index.html".to_string(), "./site/index.html")];
+    /// #
+    /// let upload = shdw_drive_client
+    ///     .store_files_if_changed(&storage_account_key, files)
+    ///     .await?;
+    /// println!("Unchanged: {:?}", upload.skipped_files);
+    /// ```
+    pub async fn store_files_if_changed(
+        &self,
+        storage_account_key: &Pubkey,
+        data: Vec<ShadowFile>,
+    ) -> ShadowDriveResult<ShadowUploadResponse> {
+        let checks = data.into_iter().map(|file| async move {
+            let unchanged = self.is_object_unchanged(storage_account_key, &file).await?;
+            Ok::<_, Error>((file, unchanged))
+        });
+
+        let mut changed = vec![];
+        let mut skipped_files = vec![];
+        for check in join_all(checks).await {
+            match check? {
+                (file, true) => skipped_files.push(file.name),
+                (file, false) => changed.push(file),
+            }
+        }
+
+        let mut response = if changed.is_empty() {
+            ShadowUploadResponse {
+                message: "all files unchanged".to_string(),
+                ..Default::default()
+            }
+        } else {
+            self.store_files(storage_account_key, changed).await?
+        };
+        response.skipped_files = skipped_files;
+        Ok(response)
+    }
+
+    /// Uploads only the files whose name is not already present in the storage account,
+    /// as in [`store_files`](Self::store_files), so an interrupted upload can be re-run.
+    /// Files that exist are listed in [`ShadowUploadResponse::skipped_files`] rather than
+    /// uploaded, whatever their contents; use [`store_files_if_changed`](Self::store_files_if_changed)
+    /// to also re-upload files whose contents changed. Existence is checked with a single
+    /// [`list_objects`](Self::list_objects) call.
+    /// * `storage_account_key` - The public key of the [`StorageAccount`](crate::models::StorageAccount) to upload to.
+    /// * `data` - The files to upload if missing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account, models::ShadowFile};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// # let files = vec![ShadowFile::file("0.json".to_string(), "./metadata/0.json")];
+    /// #
+    /// let upload = shdw_drive_client
+    ///     .store_files_skip_existing(&storage_account_key, files)
+    ///     .await?;
+    /// println!(
+    ///     "Uploaded {}, skipped {}",
+    ///     upload.finalized_locations.len(),
+    ///     upload.skipped_files.len()
+    /// );
+    /// ```
+    pub async fn store_files_skip_existing(
+        &self,
+        storage_account_key: &Pubkey,
+        data: Vec<ShadowFile>,
+    ) -> ShadowDriveResult<ShadowUploadResponse> {
+        let existing: HashSet<String> = self
+            .list_objects(storage_account_key)
+            .await?
+            .into_iter()
+            .collect();
+        let (skipped, missing): (Vec<_>, Vec<_>) = data
+            .into_iter()
+            .partition(|file| existing.contains(file.name()));
+
+        let mut response = if missing.is_empty() {
+            ShadowUploadResponse {
+                message: "all files already exist".to_string(),
+                ..Default::default()
+            }
+        } else {
+            self.store_files(storage_account_key, missing).await?
+        };
+        response.skipped_files = skipped.into_iter().map(|file| file.name).collect();
+        Ok(response)
+    }
+
+    /// Whether the stored object named like `file` already has the same contents.
+    async fn is_object_unchanged(
+        &self,
+        storage_account_key: &Pubkey,
+        file: &ShadowFile,
+    ) -> ShadowDriveResult<bool> {
+        if matches!(file.data, Payload::Stream(_)) {
+            return Ok(false);
+        }
+        let local_md5 = match file.md5_digest().await? {
+            Some(md5) => hex::encode(md5),
+            None => return Ok(false),
+        };
+
+        let response = self
+            .http_client
+            .head(format!(
+                "{}/{}/{}",
+                SHDW_DRIVE_OBJECT_PREFIX, storage_account_key, file.name
+            ))
+            .header(header::IF_NONE_MATCH, format!("\"{}\"", local_md5))
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(true);
+        }
+        if !response.status().is_success() {
+            return Ok(false);
+        }
+
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| {
+                etag.trim_start_matches("W/")
+                    .trim_matches('"')
+                    .to_ascii_lowercase()
+            });
+        match etag {
+            Some(etag) if etag.len() == 32 && etag.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Ok(etag == local_md5)
+            }
+            // Multipart ETags are not a digest of the contents; compare hashes directly.
+            _ => {
+                self.remote_object_matches(
+                    storage_account_key,
+                    &file.name,
+                    file.content_len().await?,
+                    &file.sha256_digest().await?,
+                )
+                .await
+            }
+        }
+    }
+}
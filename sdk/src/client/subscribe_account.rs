@@ -0,0 +1,121 @@
+use futures::{stream, Stream, StreamExt};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{nonblocking::pubsub_client::PubsubClient, rpc_config::RpcAccountInfoConfig};
+use solana_sdk::{account::Account, pubkey::Pubkey, signer::Signer};
+use tokio::sync::{mpsc, oneshot};
+
+use super::{get_storage_account::storage_account_from_data, ShadowDriveClient};
+use crate::{
+    error::Error,
+    models::{ShadowDriveResult, StorageAccountInfo},
+};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Subscribes to a storage account over the RPC websocket (`accountSubscribe`) and yields
+    /// its new state every time it changes on-chain. The websocket URL is derived from the
+    /// client's RPC URL. The stream ends when the connection closes; dropping it unsubscribes.
+    /// Updates that fail to decode as a storage account are skipped.
+    /// Requires the `pubsub` feature.
+    /// * `key` - The public key of the storage account to watch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// # use futures::StreamExt;
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let mut updates = shdw_drive_client
+    ///     .subscribe_account(&storage_account_key)
+    ///     .await?;
+    /// while let Some(account) = updates.next().await {
+    ///     println!("{} bytes reserved", account.storage());
+    /// }
+    /// ```
+    pub async fn subscribe_account(
+        &self,
+        key: &Pubkey,
+    ) -> ShadowDriveResult<impl Stream<Item = StorageAccountInfo>> {
+        let pubsub_client = PubsubClient::new(&websocket_url(&self.rpc_client.url()))
+            .await
+            .map_err(|err| Error::PubsubError(err.to_string()))?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.rpc_client.commitment()),
+            ..Default::default()
+        };
+
+        let key = *key;
+        let (subscribed_tx, subscribed_rx) = oneshot::channel();
+        let (updates_tx, updates_rx) = mpsc::unbounded_channel();
+
+        // The subscription borrows the pubsub client, so both live in a task that
+        // forwards decoded updates until the connection or the receiver goes away.
+        tokio::spawn(async move {
+            let (mut updates, unsubscribe) =
+                match pubsub_client.account_subscribe(&key, Some(config)).await {
+                    Ok(subscription) => {
+                        let _ = subscribed_tx.send(Ok(()));
+                        subscription
+                    }
+                    Err(err) => {
+                        let _ = subscribed_tx.send(Err(Error::PubsubError(err.to_string())));
+                        return;
+                    }
+                };
+
+            while let Some(update) = updates.next().await {
+                let account = update
+                    .value
+                    .decode::<Account>()
+                    .ok_or(Error::InvalidAccountDiscriminator)
+                    .and_then(|account| storage_account_from_data(&key, &account.data));
+                match account {
+                    Ok(account) => {
+                        if updates_tx.send(StorageAccountInfo::from(account)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        tracing::debug!(?err, %key, "skipping undecodable account update")
+                    }
+                }
+            }
+
+            unsubscribe().await;
+        });
+
+        subscribed_rx
+            .await
+            .map_err(|_| Error::PubsubError("subscription task exited".into()))??;
+
+        Ok(stream::unfold(updates_rx, |mut updates_rx| async move {
+            updates_rx.recv().await.map(|account| (account, updates_rx))
+        }))
+    }
+}
+
+/// Maps an `http(s)://` RPC URL to the matching `ws(s)://` URL.
+fn websocket_url(rpc_url: &str) -> String {
+    if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        rpc_url.to_string()
+    }
+}
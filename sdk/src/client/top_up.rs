@@ -4,7 +4,9 @@ use crate::{
     models::{ShadowDriveResult, ShdwDriveResponse},
     ShadowDriveClient,
 };
-use solana_sdk::{pubkey::Pubkey, signer::Signer, transaction::Transaction};
+use solana_sdk::{
+    instruction::Instruction, pubkey::Pubkey, signer::Signer, transaction::Transaction,
+};
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::instruction::transfer;
 
@@ -42,27 +44,40 @@ where
         storage_account_key: &Pubkey,
         amount: u64,
     ) -> ShadowDriveResult<ShdwDriveResponse> {
+        let wallet_pubkey = self.wallet.pubkey();
+        let instruction = self.top_up_instruction(storage_account_key, amount);
+
+        let mut txn = Transaction::new_with_payer(
+            &self.prepend_priority_fee(&[instruction]),
+            Some(&wallet_pubkey),
+        );
+        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
+        txn.try_sign(&[&self.wallet], recent_blockhash)?;
+        let txn_result = self.send_and_confirm_transaction(&txn).await?;
+
+        Ok(ShdwDriveResponse {
+            txid: txn_result.to_string(),
+        })
+    }
+
+    /// Builds the SHDW transfer from the wallet's token account to the storage account's stake account.
+    pub(crate) fn top_up_instruction(
+        &self,
+        storage_account_key: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
         let wallet_pubkey = self.wallet.pubkey();
         let owner_ata = get_associated_token_address(&wallet_pubkey, &TOKEN_MINT);
         let (stake_account, _) = derived_addresses::stake_account(storage_account_key);
 
-        let instruction = transfer(
+        transfer(
             &spl_token::id(),
             &owner_ata,
             &stake_account,
-            &self.wallet.pubkey(),
-            &[&self.wallet.pubkey()],
+            &wallet_pubkey,
+            &[&wallet_pubkey],
             amount,
         )
-        .unwrap();
-
-        let mut txn = Transaction::new_with_payer(&[instruction], Some(&wallet_pubkey));
-        let recent_blockhash = self.rpc_client.get_latest_blockhash().await?;
-        txn.try_sign(&[&self.wallet], recent_blockhash)?;
-        let txn_result = self.rpc_client.send_and_confirm_transaction(&txn).await?;
-
-        Ok(ShdwDriveResponse {
-            txid: txn_result.to_string(),
-        })
+        .unwrap()
     }
 }
@@ -0,0 +1,62 @@
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use super::ShadowDriveClient;
+use crate::constants::{SHDW_DRIVE_ENDPOINT, UPLOADER};
+
+/// The uploader that co-signs storage account transactions, set with
+/// [`with_uploader`](ShadowDriveClient::with_uploader).
+///
+/// The Shadow Drive program requires the `uploader` of the [`StorageConfig`](crate::StorageConfig)
+/// to sign account creation and every change to an account's size or mutability. The client
+/// cannot hold that key, so it builds these transactions with `pubkey` as the uploader, signs
+/// them with its own wallet, and posts them to `endpoint`. The server there adds the uploader's
+/// signature and submits the transaction. By default this is GenesysGo's hosted upload server,
+/// which signs as [`UPLOADER`]. A self-hosted or test deployment of the program configured with a
+/// different uploader key needs its own server that signs with that key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UploaderConfig {
+    /// Must match the `uploader` of the program's [`StorageConfig`](crate::StorageConfig).
+    pub pubkey: Pubkey,
+    /// Base URL of the server holding the uploader key, e.g. `https://shadow-storage.genesysgo.net`.
+    pub endpoint: String,
+}
+
+impl Default for UploaderConfig {
+    fn default() -> Self {
+        Self {
+            pubkey: UPLOADER,
+            endpoint: SHDW_DRIVE_ENDPOINT.to_string(),
+        }
+    }
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Sets the uploader that co-signs storage account transactions, for deployments whose
+    /// [`StorageConfig`](crate::StorageConfig) names a different uploader than the hosted Shadow Drive.
+    /// Affects [`create_storage_account`](Self::create_storage_account), storage changes such as
+    /// [`add_storage`](Self::add_storage) and [`reduce_storage`](Self::reduce_storage), and
+    /// [`make_storage_immutable`](Self::make_storage_immutable). File uploads are unaffected.
+    /// See [`UploaderConfig`] for how the uploader's signature is obtained.
+    ///
+    /// # Example
+    /// ```
+    /// use shadow_drive_rust::UploaderConfig;
+    /// use solana_sdk::{pubkey::Pubkey, signer::keypair::Keypair};
+    ///
+    /// let wallet = Keypair::generate();
+    /// let shdw_drive = ShadowDriveClient::new(wallet, "http://localhost:8899").with_uploader(
+    ///     UploaderConfig {
+    ///         pubkey: Pubkey::new_unique(),
+    ///         endpoint: "http://localhost:3000".to_string(),
+    ///     },
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_uploader(mut self, uploader: UploaderConfig) -> Self {
+        self.uploader = uploader;
+        self
+    }
+}
@@ -0,0 +1,144 @@
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature, signer::Signer};
+use std::{str::FromStr, time::Duration};
+
+use super::ShadowDriveClient;
+use crate::{error::Error, models::ShadowDriveResult};
+
+/// How [`wait_for_confirmation`](ShadowDriveClient::wait_for_confirmation) polls a transaction.
+#[derive(Clone, Debug)]
+pub struct ConfirmationConfig {
+    /// The commitment level the transaction must reach.
+    pub commitment: CommitmentConfig,
+    /// How long to wait after the first signature status check. Doubles after each
+    /// further check, up to [`max_poll_interval`](Self::max_poll_interval).
+    pub poll_interval: Duration,
+    /// The longest wait between signature status checks.
+    pub max_poll_interval: Duration,
+    /// The last block height at which the transaction's blockhash is valid, as returned with it by
+    /// `get_latest_blockhash_with_commitment`. Once the cluster is past it and the transaction has not
+    /// landed, it never will, and polling stops with [`Error::TransactionExpired`]. If `None`, the last
+    /// valid block height of the latest blockhash when polling starts is used, which is never earlier.
+    pub last_valid_block_height: Option<u64>,
+}
+
+impl ConfirmationConfig {
+    /// Wait for finalized commitment, polling after 1 second and backing off to every 8 seconds,
+    /// until the transaction lands or its blockhash expires.
+    pub fn finalized() -> Self {
+        Self {
+            commitment: CommitmentConfig::finalized(),
+            poll_interval: Duration::from_secs(1),
+            max_poll_interval: Duration::from_secs(8),
+            last_valid_block_height: None,
+        }
+    }
+
+    /// Stop polling once the cluster is past `last_valid_block_height`, the deadline of the
+    /// blockhash the transaction was signed with.
+    #[must_use]
+    pub fn with_last_valid_block_height(mut self, last_valid_block_height: u64) -> Self {
+        self.last_valid_block_height = Some(last_valid_block_height);
+        self
+    }
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self::finalized()
+    }
+}
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Polls a transaction until it reaches the commitment in `config`.
+    /// Mutating methods return once their transaction is confirmed by the client's
+    /// [`RpcClient`](solana_client::nonblocking::rpc_client::RpcClient), which may be
+    /// before it is finalized; waiting avoids reading stale state right afterwards.
+    /// Polling backs off exponentially, and stops when the transaction's blockhash expires
+    /// rather than after a fixed time, so slow but valid transactions are not given up on.
+    /// * `txid` - The base58 signature of the transaction, as found in the method's response.
+    /// * `config` - The commitment to wait for and how to poll.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, ConfirmationConfig, derived_addresses::storage_account};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// #
+    /// let response = shdw_drive_client
+    ///     .delete_storage_account(&storage_account_key)
+    ///     .await?;
+    /// shdw_drive_client
+    ///     .wait_for_confirmation(&response.txid, &ConfirmationConfig::finalized())
+    ///     .await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidSignature`] if `txid` is not a transaction signature.
+    /// - [`Error::TransactionExpired`] if the transaction's blockhash expired before it landed.
+    /// - [`Error::SolanaRpcError`] if the transaction failed, or on network failures.
+    pub async fn wait_for_confirmation(
+        &self,
+        txid: &str,
+        config: &ConfirmationConfig,
+    ) -> ShadowDriveResult<()> {
+        let signature =
+            Signature::from_str(txid).map_err(|_| Error::InvalidSignature(txid.to_string()))?;
+
+        let last_valid_block_height = match config.last_valid_block_height {
+            Some(height) => height,
+            // The newest blockhash expires no earlier than the one the transaction was signed with.
+            None => {
+                self.rpc_client
+                    .get_latest_blockhash_with_commitment(CommitmentConfig::processed())
+                    .await?
+                    .1
+            }
+        };
+
+        let mut poll_interval = config.poll_interval;
+        loop {
+            let status = self
+                .rpc_client
+                .get_signature_status_with_commitment(&signature, config.commitment)
+                .await?;
+            match status {
+                Some(Ok(())) => return Ok(()),
+                Some(Err(err)) => return Err(Error::SolanaRpcError(err.into())),
+                None => {}
+            }
+
+            let block_height = self
+                .rpc_client
+                .get_block_height_with_commitment(config.commitment)
+                .await?;
+            if block_height > last_valid_block_height {
+                // Too late to land now, but it may have landed without reaching `commitment` yet.
+                let landed = self
+                    .rpc_client
+                    .get_signature_status_with_commitment(&signature, CommitmentConfig::processed())
+                    .await?;
+                if landed.is_none() {
+                    return Err(Error::TransactionExpired(txid.to_string()));
+                }
+            }
+
+            tokio::time::sleep(poll_interval).await;
+            poll_interval = (poll_interval * 2).min(config.max_poll_interval);
+        }
+    }
+}
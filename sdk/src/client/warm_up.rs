@@ -0,0 +1,45 @@
+use futures::future::try_join_all;
+use solana_sdk::signer::Signer;
+
+use super::ShadowDriveClient;
+use crate::{constants::SHDW_DRIVE_ENDPOINT, models::ShadowDriveResult};
+
+impl<T> ShadowDriveClient<T>
+where
+    T: Signer,
+{
+    /// Opens connections to the upload server ahead of a batch of uploads, so the first
+    /// uploads don't pay for connection and TLS setup. One connection is primed per upload
+    /// [`UploadLimits::max_concurrency`](crate::UploadLimits::max_concurrency), with concurrent
+    /// minimal requests whose connections then stay in the client's pool for reuse.
+    /// Idle connections are eventually closed by the pool, so call this shortly before uploading,
+    /// e.g. right before [`store_files_stream`](Self::store_files_stream).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use shadow_drive_rust::{ShadowDriveClient, derived_addresses::storage_account, models::ShadowFile};
+    /// # use solana_client::rpc_client::RpcClient;
+    /// # use solana_sdk::{
+    /// # pubkey::Pubkey,
+    /// # signature::Keypair,
+    /// # signer::{keypair::read_keypair_file, Signer},
+    /// # };
+    /// #
+    /// # let keypair = read_keypair_file(KEYPAIR_PATH).expect("failed to load keypair at path");
+    /// # let user_pubkey = keypair.pubkey();
+    /// # let rpc_client = RpcClient::new("https://ssc-dao.genesysgo.net");
+    /// # let shdw_drive_client = ShadowDriveClient::new(keypair, rpc_client);
+    /// # let (storage_account_key, _) = storage_account(&user_pubkey, 0);
+    /// # let files = vec![ShadowFile::file("example.png".to_string(), "./example.png")];
+    /// #
+    /// shdw_drive_client.warm_up().await?;
+    /// let uploads = shdw_drive_client.store_files_stream(&storage_account_key, files);
+    /// ```
+    pub async fn warm_up(&self) -> ShadowDriveResult<()> {
+        let connections = self.upload_limits.max_concurrency.max(1);
+        try_join_all((0..connections).map(|_| self.http_client.head(SHDW_DRIVE_ENDPOINT).send()))
+            .await?;
+        Ok(())
+    }
+}
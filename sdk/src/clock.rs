@@ -0,0 +1,59 @@
+//! Sources of the current Solana epoch.
+//!
+//! Client logic that depends on "now", such as whether unstaked tokens can be claimed,
+//! reads the current epoch through an [`EpochSource`]. By default this is the client's
+//! [`RpcClient`]; tests can supply a [`FixedEpoch`] via
+//! [`ShadowDriveClient::with_epoch_source`](crate::ShadowDriveClient::with_epoch_source).
+use async_trait::async_trait;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::clock::Epoch;
+
+use crate::models::ShadowDriveResult;
+
+/// Provides the current Solana epoch.
+#[async_trait]
+pub trait EpochSource: Send + Sync {
+    async fn current_epoch(&self) -> ShadowDriveResult<Epoch>;
+}
+
+#[async_trait]
+impl EpochSource for RpcClient {
+    async fn current_epoch(&self) -> ShadowDriveResult<Epoch> {
+        Ok(self.get_epoch_info().await?.epoch)
+    }
+}
+
+/// An [`EpochSource`] that always reports the same epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedEpoch(pub Epoch);
+
+#[async_trait]
+impl EpochSource for FixedEpoch {
+    async fn current_epoch(&self) -> ShadowDriveResult<Epoch> {
+        Ok(self.0)
+    }
+}
+
+/// Returns whether stake unstaked in `epoch_last_unstaked` can be claimed in `current_epoch`.
+/// Unstaked tokens become claimable once the epoch in which storage was reduced has ended.
+pub fn stake_claimable(epoch_last_unstaked: Epoch, current_epoch: Epoch) -> bool {
+    current_epoch > epoch_last_unstaked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_epoch_reports_its_epoch() {
+        let epoch = futures::executor::block_on(FixedEpoch(42).current_epoch()).unwrap();
+        assert_eq!(epoch, 42);
+    }
+
+    #[test]
+    fn stake_claimable_after_unstake_epoch() {
+        assert!(!stake_claimable(100, 99));
+        assert!(!stake_claimable(100, 100));
+        assert!(stake_claimable(100, 101));
+    }
+}
@@ -1,5 +1,6 @@
 use lazy_static::lazy_static;
 use solana_sdk::{pubkey, pubkey::Pubkey};
+use std::time::Duration;
 
 /// Address of the Mainnet Shadow Drive Program.
 pub static PROGRAM_ADDRESS: Pubkey = pubkey!("2e1wdyNhUvE76y6yUCvah2KaviavMJYKoRun8acMRBZZ");
@@ -19,4 +20,19 @@ lazy_static! {
 pub const SHDW_DRIVE_ENDPOINT: &str = "https://shadow-storage.genesysgo.net";
 pub const SHDW_DRIVE_OBJECT_PREFIX: &str = "https://shdw-drive.genesysgo.net";
 
+/// Largest file, in bytes, the upload server accepts in a single request.
+/// The server has no API for assembling a file from separately uploaded parts, so larger
+/// files are rejected with [`Error::FileTooLarge`](crate::error::Error::FileTooLarge)
+/// before anything is sent rather than split.
 pub const FILE_SIZE_LIMIT: u64 = 1_073_741_824; //1GB
+
+/// Typical time between an upload finalizing and the object being served at its
+/// [`SHDW_DRIVE_OBJECT_PREFIX`] URL. This is a best-effort estimate of the gateway's usual
+/// behavior, not a guarantee: under load, propagation can take considerably longer.
+pub const ESTIMATED_PROPAGATION_DELAY: Duration = Duration::from_secs(2);
+
+/// Maximum length in bytes of a storage account identifier, as enforced on-chain.
+pub const MAX_IDENTIFIER_SIZE: usize = 64;
+
+/// Number of bytes in a GiB. On-chain storage pricing is denominated per GiB.
+pub const BYTES_PER_GIB: u64 = 1 << 30;
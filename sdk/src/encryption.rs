@@ -0,0 +1,61 @@
+//! Client-side encryption of uploads, see [`ShadowFileBuilder::encrypt`](crate::models::ShadowFileBuilder::encrypt).
+//!
+//! Contents are sealed with AES-256-GCM before upload; the stored object is the
+//! 12-byte nonce followed by the ciphertext. Keys are never uploaded: keeping them
+//! safe, and not losing them, is the caller's responsibility. An object cannot be
+//! recovered without the key it was encrypted with.
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use std::fmt;
+
+use crate::{error::Error, models::ShadowDriveResult};
+
+/// Length in bytes of an AES-256 key.
+pub const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// A 256-bit symmetric key. Its `Debug` output is redacted.
+#[derive(Clone)]
+pub struct EncryptionKey([u8; KEY_LEN]);
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+impl From<[u8; KEY_LEN]> for EncryptionKey {
+    fn from(bytes: [u8; KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl EncryptionKey {
+    /// Encrypts `plaintext` under a fresh random nonce, returning nonce || ciphertext.
+    pub fn encrypt(&self, plaintext: &[u8]) -> ShadowDriveResult<Vec<u8>> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| Error::EncryptionError("failed to encrypt contents".into()))?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Inverse of [`encrypt`](Self::encrypt). Fails with [`Error::EncryptionError`]
+    /// if the key is wrong or the data was altered.
+    pub fn decrypt(&self, data: &[u8]) -> ShadowDriveResult<Vec<u8>> {
+        if data.len() < NONCE_LEN {
+            return Err(Error::EncryptionError("encrypted data is too short".into()));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                Error::EncryptionError("failed to decrypt: wrong key or corrupted data".into())
+            })
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
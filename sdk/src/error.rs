@@ -1,18 +1,46 @@
 use anchor_lang::error::Error as AnchorError;
 use reqwest::Error as ReqwestError;
 use solana_client::client_error::ClientError;
-use solana_sdk::pubkey::ParsePubkeyError;
+use solana_sdk::pubkey::{ParsePubkeyError, Pubkey};
 use solana_sdk::signer::SignerError;
 use std::io::Error as IoError;
+use std::path::PathBuf;
 use tokio::task::JoinError;
 
+/// The result type returned by every fallible [`ShadowDriveClient`](crate::ShadowDriveClient)
+/// method, re-exported at the crate root.
+///
+/// Errors fall into a few groups, which each method's `# Errors` section refers to:
+/// - Network and RPC failures: [`Error::SolanaRpcError`], [`Error::ReqwestError`] and
+///   [`Error::PubsubError`]. These are usually transient and safe to retry.
+/// - Gateway rejections: [`Error::ShadowDriveServerError`] with the HTTP status and body
+///   returned by the Shadow Drive upload server.
+/// - Validation failures, raised before anything is sent: e.g. [`Error::FileValidationError`],
+///   [`Error::FileTooLarge`], [`Error::InvalidIdentifier`] and [`Error::SizeOutOfRange`].
+/// - Account state: [`Error::InvalidAccountDiscriminator`], [`Error::AccountDeserializeError`],
+///   [`Error::StorageAccountIsNotImmutable`] and [`Error::AccountMarkedForDeletion`].
+/// - Signing and transaction building: [`Error::SignerError`], [`Error::AnchorError`] and
+///   [`Error::TransactionSerializationFailed`].
+pub type ShadowDriveResult<T> = Result<T, Error>;
+
 #[derive(Debug)]
 pub enum Error {
     ShadowDriveServerError {
         status: u16,
         message: serde_json::Value,
     },
-    FileTooLarge(String),
+    /// A file is larger than [`FILE_SIZE_LIMIT`](crate::constants::FILE_SIZE_LIMIT),
+    /// the most the upload server accepts in one request. Sizes are in bytes.
+    FileTooLarge {
+        file: String,
+        size: u64,
+        max: u64,
+    },
+    /// A file has no contents. The upload server does not reliably store
+    /// zero-byte files, so they are rejected before upload.
+    EmptyFile(String),
+    /// The operation is not supported by the Shadow Drive gateway. Holds the reason.
+    Unsupported(String),
     TransactionSerializationFailed(String),
     InvalidJson(serde_json::Error),
     SolanaRpcError(ClientError),
@@ -28,6 +56,57 @@ pub enum Error {
     ParsePubkeyError(ParsePubkeyError),
     NotFileOwner,
     StorageAccountIsNotImmutable,
+    InvalidAccountDiscriminator,
+    InvalidTokenBalance(String),
+    ClaimStakeTimeout,
+    IdentifierTooLong {
+        len: usize,
+        max: usize,
+    },
+    /// The storage account identifier is empty or contains a disallowed character.
+    /// Holds the reason.
+    InvalidIdentifier(String),
+    /// The requested storage account size, in bytes, is outside the bounds
+    /// set by the on-chain `StorageConfig`.
+    SizeOutOfRange {
+        min: u64,
+        max: u64,
+        requested: u64,
+    },
+    /// A storage account cannot be resized to fewer bytes than it currently stores.
+    SizeBelowUsage {
+        used: u64,
+        requested: u64,
+    },
+    StreamAlreadyConsumed(String),
+    StreamNotUploaded(String),
+    InvalidSizeString(String),
+    InvalidSignature(String),
+    ConfirmationTimeout(String),
+    /// The blockhash of the transaction with this signature expired before the transaction
+    /// landed, so it will never be confirmed and can be safely re-sent.
+    TransactionExpired(String),
+    InvalidFileName(PathBuf),
+    /// A [`ShadowFileBuilder`](crate::models::ShadowFileBuilder) was given missing or
+    /// conflicting options. Holds the reason.
+    InvalidShadowFile(String),
+    IncompatibleProgram(String),
+    InvalidObjectMetadata(String),
+    /// The gateway served an error page where an object was expected,
+    /// e.g. a CDN error page with a 200 status. Holds the reason.
+    GatewayError(String),
+    /// The body of a list-objects response is not a well-formed list of keys.
+    InvalidListResponse(String),
+    InvalidUtf8(String),
+    /// The storage account is marked for deletion and cannot be written to.
+    /// Call `cancel_delete_storage_account` to unmark it first.
+    AccountMarkedForDeletion(Pubkey),
+    AccountNotMarkedForDeletion(Pubkey),
+    PubsubError(String),
+    ObjectNotAvailable(Vec<String>),
+    /// Encrypting or decrypting with an `EncryptionKey` failed, e.g. because
+    /// of a wrong key or altered data. Holds the reason.
+    EncryptionError(String),
 }
 
 #[derive(Debug)]
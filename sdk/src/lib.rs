@@ -14,14 +14,25 @@
 mod client;
 pub use client::*;
 
+pub mod clock;
 pub mod constants;
 pub mod derived_addresses;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod error;
+pub mod merkle;
+mod metrics;
 pub mod models;
+pub mod object_store;
+#[cfg(any(test, feature = "test-utilities"))]
+pub mod test_utilities;
+pub mod units;
 
 pub use {
     // allows users to specify number of bytes
     byte_unit::Byte,
+    // the error type and result alias returned by every client method
+    error::{Error, ShadowDriveResult},
     // allows users to deserialize type
     shadow_drive_user_staking::instructions::{
         initialize_account::StorageAccount, initialize_config::StorageConfig,
@@ -0,0 +1,155 @@
+//! Merkle trees over uploaded files, for publishing a single root that commits to
+//! every file of an upload.
+//!
+//! Leaves are `SHA-256(0x00 || len(name) as u64 LE || name || SHA-256(contents))`,
+//! ordered by file name, and inner nodes are `SHA-256(0x01 || left || right)`.
+//! A node without a sibling is carried up to the next level unchanged.
+//! The root of an empty tree is all zeroes.
+use sha2::{Digest, Sha256};
+
+/// Hash of a single file, binding its name to its SHA-256 digest.
+pub fn leaf_hash(name: &str, file_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update((name.len() as u64).to_le_bytes());
+    hasher.update(name.as_bytes());
+    hasher.update(file_hash);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// One step from a node towards the root: the hash of its sibling, and which side the sibling is on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofStep {
+    pub sibling: [u8; 32],
+    pub sibling_is_left: bool,
+}
+
+/// Proof that a file with a given name and contents is part of a Merkle root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub name: String,
+    /// SHA-256 digest of the file's contents.
+    pub file_hash: [u8; 32],
+    pub path: Vec<ProofStep>,
+}
+
+impl MerkleProof {
+    /// Returns whether this proof places the file under `root`.
+    pub fn verify(&self, root: &[u8; 32]) -> bool {
+        let computed =
+            self.path
+                .iter()
+                .fold(leaf_hash(&self.name, &self.file_hash), |node, step| {
+                    if step.sibling_is_left {
+                        node_hash(&step.sibling, &node)
+                    } else {
+                        node_hash(&node, &step.sibling)
+                    }
+                });
+        &computed == root
+    }
+}
+
+/// Sorts files by name and returns their leaf hashes.
+fn sorted_leaves(files: &[(String, [u8; 32])]) -> Vec<(&str, [u8; 32])> {
+    let mut leaves: Vec<_> = files
+        .iter()
+        .map(|(name, file_hash)| (name.as_str(), leaf_hash(name, file_hash)))
+        .collect();
+    leaves.sort_by(|(a, _), (b, _)| a.cmp(b));
+    leaves
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Computes the Merkle root of `files`, given as `(name, SHA-256 of contents)` pairs in any order.
+pub fn merkle_root(files: &[(String, [u8; 32])]) -> [u8; 32] {
+    let mut level: Vec<_> = sorted_leaves(files)
+        .into_iter()
+        .map(|(_, leaf)| leaf)
+        .collect();
+    if level.is_empty() {
+        return [0; 32];
+    }
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Builds the proof that file `name` is part of [`merkle_root(files)`](merkle_root).
+pub fn merkle_proof(files: &[(String, [u8; 32])], name: &str) -> Option<MerkleProof> {
+    let (_, file_hash) = files.iter().find(|(file, _)| file == name)?;
+    let leaves = sorted_leaves(files);
+    let mut index = leaves.iter().position(|(file, _)| *file == name)?;
+    let mut level: Vec<_> = leaves.into_iter().map(|(_, leaf)| leaf).collect();
+
+    let mut path = Vec::new();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            path.push(ProofStep {
+                sibling: level[sibling],
+                sibling_is_left: sibling < index,
+            });
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+
+    Some(MerkleProof {
+        name: name.to_string(),
+        file_hash: *file_hash,
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(count: u8) -> Vec<(String, [u8; 32])> {
+        (0..count)
+            .map(|i| (format!("file-{}.txt", i), Sha256::digest([i]).into()))
+            .collect()
+    }
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        for count in 1..=7 {
+            let files = files(count);
+            let root = merkle_root(&files);
+            for (name, _) in &files {
+                let proof = merkle_proof(&files, name).unwrap();
+                assert!(proof.verify(&root), "{} of {} files", name, count);
+            }
+        }
+    }
+
+    #[test]
+    fn tampered_file_does_not_verify() {
+        let files = files(5);
+        let root = merkle_root(&files);
+        let mut proof = merkle_proof(&files, "file-3.txt").unwrap();
+        proof.file_hash[0] ^= 1;
+        assert!(!proof.verify(&root));
+        assert!(merkle_proof(&files, "missing.txt").is_none());
+    }
+}
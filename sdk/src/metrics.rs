@@ -0,0 +1,33 @@
+//! Upload metrics, recorded through the [`metrics`](https://docs.rs/metrics) facade
+//! when the `metrics` feature is enabled. Without the feature these functions are no-ops.
+//!
+//! Counters:
+//! * `shadow_drive_uploads_total` - files uploaded successfully
+//! * `shadow_drive_upload_failures_total` - files that failed to upload
+//! * `shadow_drive_upload_bytes_total` - bytes uploaded successfully
+//! * `shadow_drive_retries_total` - requests retried after a transient error
+//!
+//! Histograms:
+//! * `shadow_drive_upload_latency_seconds` - duration of each upload request
+use std::time::Duration;
+
+/// Records the outcome of a single upload request.
+#[inline]
+pub(crate) fn record_upload(succeeded: u64, failed: u64, bytes: u64, latency: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        ::metrics::counter!("shadow_drive_uploads_total", succeeded);
+        ::metrics::counter!("shadow_drive_upload_failures_total", failed);
+        ::metrics::counter!("shadow_drive_upload_bytes_total", bytes);
+        ::metrics::histogram!("shadow_drive_upload_latency_seconds", latency.as_secs_f64());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (succeeded, failed, bytes, latency);
+}
+
+/// Records that a request is being retried after a transient error.
+#[inline]
+pub(crate) fn record_retry() {
+    #[cfg(feature = "metrics")]
+    ::metrics::increment_counter!("shadow_drive_retries_total");
+}
@@ -1,30 +1,67 @@
 use bytes::Bytes;
-use reqwest::multipart::Part;
+use chrono::DateTime;
+use md5::Md5;
+use reqwest::{header::HeaderMap, multipart::Part, Body};
 use serde::Deserialize;
-use sha2::{Digest, Sha256};
-use std::path::Path;
-use tokio::{fs::File, io::AsyncReadExt};
+use sha2::{digest::Output, Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::{Path, PathBuf},
+};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt},
+};
 
 //re-export structs from Shadow Drive Smart Contract that are used in the SDK
 pub use shadow_drive_user_staking::instructions::{
     decrease_storage::UnstakeInfo, initialize_account::UserInfo, store_file::File as FileAccount,
 };
 
+mod file_builder;
 pub mod payload;
 pub mod storage_acct;
 
-use crate::{constants::FILE_SIZE_LIMIT, error::Error};
-use payload::Payload;
+#[cfg(feature = "compression")]
+pub use file_builder::Compression;
+pub use file_builder::ShadowFileBuilder;
+pub use storage_acct::{ShadowDriveStorageAccount, StorageAccountInfo};
+
+use crate::{
+    constants::FILE_SIZE_LIMIT,
+    error::Error,
+    merkle::{self, MerkleProof},
+};
+use payload::{ByteStream, Payload, StreamPayload};
 
-pub type ShadowDriveResult<T> = Result<T, Error>;
+pub use crate::error::ShadowDriveResult;
 
 const BUFFER_SIZE: usize = 4096;
+/// Chunk size used when streaming reader-backed files.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct ShdwDriveResponse {
     pub txid: String,
 }
 
+#[derive(Clone, Debug)]
+pub struct CrankResponse {
+    pub txid: String,
+    /// Mutable storage fees, in shades, collected from the account's stake.
+    pub fees_collected: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramVersion {
+    /// Slot in which the Shadow Drive program was last deployed or upgraded.
+    pub deployed_slot: u64,
+    /// Authority allowed to upgrade the program, or `None` if it is immutable.
+    pub upgrade_authority: Option<Pubkey>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct StorageResponse {
     pub message: String,
@@ -56,11 +93,20 @@ pub struct ShadowFile {
     pub name: String,
     pub data: Payload,
     content_type: String,
+    /// Extra headers sent with the file's part of the upload request.
+    headers: HeaderMap,
 }
 
 const FALLBACK_MIMETYPE: &'static str = "application/octet-stream";
 
 impl ShadowFile {
+    /// Starts a [`ShadowFileBuilder`], for files that need more options than
+    /// the [`file`](Self::file) and [`bytes`](Self::bytes) constructors take.
+    pub fn builder() -> ShadowFileBuilder {
+        ShadowFileBuilder::default()
+    }
+
+    /// The name the file is stored under in the storage account.
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -80,23 +126,171 @@ impl ShadowFile {
             name,
             content_type,
             data: Payload::File(path.as_ref().to_owned()),
+            headers: HeaderMap::new(),
         }
     }
 
+    /// Creates a [`ShadowFile`] named by the hex-encoded SHA-256 of its contents, so identical
+    /// content always maps to the same object name. Re-uploading content that is already stored
+    /// can then be skipped by checking the name against [`list_objects`](crate::ShadowDriveClient::list_objects).
+    ///
+    /// Two objects only share a name if their contents hash identically, which for SHA-256
+    /// means the contents are the same; an existing object with the same name can be treated as a duplicate.
+    /// * `path` - The file to upload.
+    /// * `keep_extension` - Whether to append the file's extension, e.g. `<hash>.png`, so gateways serve it with a sensible content type.
+    pub async fn content_addressed<T: AsRef<Path>>(
+        path: T,
+        keep_extension: bool,
+    ) -> ShadowDriveResult<Self> {
+        let mut file = Self::file(String::new(), path.as_ref());
+        let mut name = hex::encode(file.sha256_digest().await?);
+        if let Some(extension) = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .filter(|_| keep_extension)
+        {
+            name = format!("{}.{}", name, extension);
+        }
+        file.name = name;
+        Ok(file)
+    }
+
     pub fn bytes<T: Into<Bytes>>(name: String, data: T) -> Self {
         Self {
             name,
             content_type: FALLBACK_MIMETYPE.to_owned(),
             data: Payload::Bytes(data.into()),
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Size of the file's contents in bytes, if it can be determined without reading them:
+    /// the length of in-memory data, the declared size of a reader, or the
+    /// filesystem metadata of a file-backed payload. Returns `None` if the file's metadata can't be read.
+    pub fn len(&self) -> Option<u64> {
+        match &self.data {
+            Payload::File(path) => std::fs::metadata(path).ok().map(|metadata| metadata.len()),
+            Payload::Bytes(data) => Some(data.len() as u64),
+            Payload::Stream(stream) => Some(stream.size()),
+        }
+    }
+
+    /// Whether the file has no contents, or `None` if its size can't be determined. See [`len`](Self::len).
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// The contents of an in-memory file created with [`bytes`](Self::bytes).
+    /// Returns `None` for file- and reader-backed files, whose contents are only read during upload.
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match &self.data {
+            Payload::Bytes(data) => Some(data),
+            Payload::File(_) | Payload::Stream(_) => None,
+        }
+    }
+
+    /// Size of the file's contents in bytes, reading file metadata asynchronously.
+    pub(crate) async fn content_len(&self) -> ShadowDriveResult<u64> {
+        match &self.data {
+            Payload::File(path) => Ok(tokio::fs::metadata(path)
+                .await
+                .map_err(Error::FileSystemError)?
+                .len()),
+            Payload::Bytes(data) => Ok(data.len() as u64),
+            Payload::Stream(stream) => Ok(stream.size()),
+        }
+    }
+
+    /// Creates a [`ShadowFile`] whose contents are read lazily from a blocking [`Read`]er during upload.
+    /// `size` must be the exact number of bytes the reader will produce; it is sent as the
+    /// part's length and checked against [`FILE_SIZE_LIMIT`](crate::constants::FILE_SIZE_LIMIT) before uploading.
+    /// Reads happen on tokio's blocking thread pool.
+    ///
+    /// Reader-backed files can only be uploaded once, and their SHA-256 is only known after upload,
+    /// so they cannot be used with [`edit_file`](crate::ShadowDriveClient::edit_file).
+    pub fn from_reader<R: Read + Send + 'static>(name: String, reader: R, size: u64) -> Self {
+        let stream = futures::stream::unfold(Some(reader), |reader| async move {
+            let mut reader = reader?;
+            let (reader, chunk) = tokio::task::spawn_blocking(move || {
+                let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+                let chunk = reader.read(&mut buf).map(|bytes_read| {
+                    buf.truncate(bytes_read);
+                    Bytes::from(buf)
+                });
+                (reader, chunk)
+            })
+            .await
+            .ok()?;
+            match chunk {
+                Ok(chunk) if chunk.is_empty() => None,
+                Ok(chunk) => Some((Ok(chunk), Some(reader))),
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        Self::from_stream(name, Box::pin(stream), size)
+    }
+
+    /// Async counterpart of [`from_reader`](Self::from_reader), reading from an [`AsyncRead`]er during upload.
+    pub fn from_async_reader<R: AsyncRead + Unpin + Send + 'static>(
+        name: String,
+        reader: R,
+        size: u64,
+    ) -> Self {
+        let stream = futures::stream::unfold(Some(reader), |reader| async move {
+            let mut reader = reader?;
+            let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+            let chunk = reader.read(&mut buf).await.map(|bytes_read| {
+                buf.truncate(bytes_read);
+                Bytes::from(buf)
+            });
+            match chunk {
+                Ok(chunk) if chunk.is_empty() => None,
+                Ok(chunk) => Some((Ok(chunk), Some(reader))),
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        Self::from_stream(name, Box::pin(stream), size)
+    }
+
+    pub(crate) fn from_stream(name: String, stream: ByteStream, size: u64) -> Self {
+        Self {
+            name,
+            content_type: FALLBACK_MIMETYPE.to_owned(),
+            data: Payload::Stream(StreamPayload::new(stream, size)),
+            headers: HeaderMap::new(),
         }
     }
 
     pub(crate) async fn sha256(&self) -> ShadowDriveResult<String> {
+        Ok(hex::encode(self.sha256_digest().await?))
+    }
+
+    /// SHA-256 of the contents, as sent in the upload hint and recorded in
+    /// [`ShadowUploadResponse::file_hashes`]. Reader-backed files are hashed while they upload,
+    /// so asking before that fails with [`Error::StreamNotUploaded`].
+    pub async fn sha256_digest(&self) -> ShadowDriveResult<[u8; 32]> {
+        let digest = match &self.data {
+            // Reader-backed contents are hashed as they are uploaded.
+            Payload::Stream(stream) => stream.digest(),
+            _ => self.digest_contents::<Sha256>().await?.map(Into::into),
+        };
+        digest.ok_or_else(|| Error::StreamNotUploaded(self.name.clone()))
+    }
+
+    /// MD5 of the contents, which the gateway reports as the ETag of objects uploaded in a single part.
+    /// Returns `None` for reader-backed files, whose contents can only be read once.
+    pub(crate) async fn md5_digest(&self) -> ShadowDriveResult<Option<[u8; 16]>> {
+        Ok(self.digest_contents::<Md5>().await?.map(Into::into))
+    }
+
+    /// Hashes file-backed or in-memory contents with `D`. Returns `None` for reader-backed files.
+    async fn digest_contents<D: Digest>(&self) -> ShadowDriveResult<Option<Output<D>>> {
         let result = match &self.data {
             Payload::File(path) => {
                 let mut file = File::open(path).await.map_err(Error::FileSystemError)?;
                 let mut buf = [0u8; BUFFER_SIZE];
-                let mut hasher = Sha256::new();
+                let mut hasher = D::new();
 
                 loop {
                     let bytes_read = file.read(&mut buf[..]).await?;
@@ -111,12 +305,13 @@ impl ShadowFile {
                 hasher.finalize()
             }
             Payload::Bytes(data) => {
-                let mut hasher = Sha256::new();
+                let mut hasher = D::new();
                 hasher.update(&data);
                 hasher.finalize()
             }
+            Payload::Stream(_) => return Ok(None),
         };
-        Ok(hex::encode(result))
+        Ok(Some(result))
     }
 
     pub(crate) async fn into_form_part(self) -> ShadowDriveResult<Part> {
@@ -126,35 +321,169 @@ impl ShadowFile {
                 let file_meta = file.metadata().await.map_err(Error::FileSystemError)?;
 
                 //make sure that the file is under the size limit
-                if file_meta.len() > FILE_SIZE_LIMIT {
-                    return Err(Error::FileTooLarge(self.name.clone()));
-                }
+                check_file_size(&self.name, file_meta.len())?;
 
                 Part::stream_with_length(file, file_meta.len()).file_name(self.name)
             }
             Payload::Bytes(data) => {
                 //make sure that the file is under the size limit
-                if data.len() as u64 > FILE_SIZE_LIMIT {
-                    return Err(Error::FileTooLarge(self.name.clone()));
-                }
+                check_file_size(&self.name, data.len() as u64)?;
 
                 Part::stream_with_length(Bytes::clone(&data), data.len() as u64)
                     .file_name(self.name)
             }
+            Payload::Stream(stream) => {
+                //make sure that the file is under the size limit
+                check_file_size(&self.name, stream.size())?;
+
+                let body = stream
+                    .take()
+                    .ok_or_else(|| Error::StreamAlreadyConsumed(self.name.clone()))?;
+                Part::stream_with_length(Body::wrap_stream(body), stream.size())
+                    .file_name(self.name)
+            }
         };
 
-        part = part.mime_str(&self.content_type)?;
+        part = part.mime_str(&self.content_type)?.headers(self.headers);
         Ok(part)
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// Rejects empty contents with [`Error::EmptyFile`], and contents over
+/// [`FILE_SIZE_LIMIT`] with [`Error::FileTooLarge`].
+pub(crate) fn check_file_size(name: &str, size: u64) -> ShadowDriveResult<()> {
+    if size == 0 {
+        return Err(Error::EmptyFile(name.to_string()));
+    }
+    if size > FILE_SIZE_LIMIT {
+        return Err(Error::FileTooLarge {
+            file: name.to_string(),
+            size,
+            max: FILE_SIZE_LIMIT,
+        });
+    }
+    Ok(())
+}
+
+/// Creates a [`ShadowFile`] named after the path's basename, as with
+/// `ShadowFile::file(basename, path)`. Fails if the path has no UTF-8 file name.
+impl TryFrom<&Path> for ShadowFile {
+    type Error = Error;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| Error::InvalidFileName(path.to_path_buf()))?;
+        Ok(Self::file(name.to_string(), path))
+    }
+}
+
+impl TryFrom<&PathBuf> for ShadowFile {
+    type Error = Error;
+
+    fn try_from(path: &PathBuf) -> Result<Self, Self::Error> {
+        Self::try_from(path.as_path())
+    }
+}
+
+impl TryFrom<PathBuf> for ShadowFile {
+    type Error = Error;
+
+    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
+        Self::try_from(path.as_path())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
 pub struct ShadowUploadResponse {
     #[serde(default)]
     pub finalized_locations: Vec<String>,
     pub message: String,
     #[serde(default)]
     pub upload_errors: Vec<UploadError>,
+    /// SHA-256 digest of each uploaded file, keyed by file name.
+    /// Computed locally by the SDK during upload, not returned by the server.
+    #[serde(skip)]
+    pub file_hashes: Vec<(String, [u8; 32])>,
+    /// Size in bytes of each successfully uploaded file, keyed by file name.
+    /// Computed locally by the SDK, not returned by the server.
+    #[serde(skip)]
+    pub file_sizes: Vec<(String, u64)>,
+    /// Sum of [`file_sizes`](Self::file_sizes): the file content bytes sent for files that
+    /// were stored. Multipart framing, failed files, and bytes resent by retried
+    /// requests (see [`RetryPolicy`](crate::RetryPolicy)) are not counted.
+    #[serde(skip)]
+    pub bytes_uploaded: u64,
+    /// Files that were not uploaded because they are already stored: with the same contents for
+    /// [`store_files_if_changed`](crate::ShadowDriveClient::store_files_if_changed), or under the
+    /// same name for [`store_files_skip_existing`](crate::ShadowDriveClient::store_files_skip_existing).
+    /// Only set by those two methods.
+    #[serde(skip)]
+    pub skipped_files: Vec<String>,
+    /// Files the gateway rejected because their name is already taken, and whose stored object
+    /// was then checked to have the same size and SHA-256 as the file sent. They are treated as
+    /// finalized and included in [`finalized_locations`](Self::finalized_locations) rather than
+    /// in [`upload_errors`](Self::upload_errors). A taken name with different contents stays an
+    /// upload error.
+    #[serde(skip)]
+    pub deduplicated_files: Vec<String>,
+    /// Content hashes the gateway reported for the stored files, keyed by file name, if it
+    /// returns any. Compared against [`file_hashes`](Self::file_hashes) to set
+    /// [`integrity_verified`](Self::integrity_verified).
+    #[serde(default)]
+    pub hashes: HashMap<String, String>,
+    /// Whether each stored file's hash, as reported by the gateway in [`hashes`](Self::hashes)
+    /// or the `ETag` of a single-file upload, matches the SHA-256 computed while uploading it.
+    /// `None` when the gateway reported no SHA-256 for the file, keyed by file name.
+    #[serde(skip)]
+    pub integrity_verified: Vec<(String, Option<bool>)>,
+}
+
+impl ShadowUploadResponse {
+    /// Returns the finalized URL of the uploaded file `name`, if it was stored.
+    /// Matches on the object name in each `<host>/<storage account>/<name>` URL, so it does not
+    /// depend on the order of [`finalized_locations`](Self::finalized_locations).
+    pub fn finalized_url_for(&self, name: &str) -> Option<&str> {
+        self.finalized_locations
+            .iter()
+            .map(String::as_str)
+            .find(|location| {
+                let path = location
+                    .split_once("://")
+                    .map_or(*location, |(_, path)| path);
+                path.splitn(3, '/').nth(2) == Some(name)
+            })
+    }
+
+    /// Merkle root over the [`file_hashes`](Self::file_hashes) of the files that were stored.
+    /// See [`merkle`](crate::merkle) for how the tree is built.
+    pub fn merkle_root(&self) -> [u8; 32] {
+        merkle::merkle_root(&self.stored_file_hashes())
+    }
+
+    /// Proof that the stored file `name` is part of [`merkle_root`](Self::merkle_root),
+    /// or `None` if it was not stored.
+    pub fn proof_for(&self, name: &str) -> Option<MerkleProof> {
+        merkle::merkle_proof(&self.stored_file_hashes(), name)
+    }
+
+    /// Files whose hash reported by the gateway does not match the one computed while uploading,
+    /// i.e. those with `Some(false)` in [`integrity_verified`](Self::integrity_verified).
+    pub fn integrity_mismatches(&self) -> impl Iterator<Item = &str> {
+        self.integrity_verified
+            .iter()
+            .filter(|(_, verified)| *verified == Some(false))
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn stored_file_hashes(&self) -> Vec<(String, [u8; 32])> {
+        self.file_hashes
+            .iter()
+            .filter(|(name, _)| !self.upload_errors.iter().any(|e| &e.file == name))
+            .cloned()
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -185,6 +514,16 @@ pub enum BatchUploadStatus {
     AlreadyExists,
     Error(String),
 }
+
+/// Outcome of uploading a single file with
+/// [`store_files_stream`](crate::ShadowDriveClient::store_files_stream).
+#[derive(Debug)]
+pub struct FileUploadResult {
+    pub file_name: String,
+    /// The finalized location of the file, or why it failed to upload.
+    pub result: ShadowDriveResult<String>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct ShadowBatchUploadResponse {
     pub file_name: String,
@@ -193,6 +532,58 @@ pub struct ShadowBatchUploadResponse {
     pub transaction_signature: Option<String>,
 }
 
+/// Metadata of a stored object, read from the headers the Shadow Drive gateway serves it with.
+#[derive(Clone, Debug)]
+pub struct FileMetadata {
+    /// Time of the request, as a unix timestamp.
+    pub timestamp: i64,
+    pub content_type: String,
+    /// Time the object was last written, as a unix timestamp.
+    pub last_modified: i64,
+    pub etag: String,
+    pub storage_account: String,
+    pub storage_owner: String,
+    /// Size of the object in bytes, if the gateway reported it.
+    pub content_length: Option<u64>,
+}
+
+impl FileMetadata {
+    pub fn from_headers(h: &HeaderMap) -> ShadowDriveResult<Self> {
+        let getter = |key| {
+            h.get(key)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    Error::InvalidObjectMetadata(format!("missing file metadata header: {}", key))
+                })
+        };
+        let parse_timestamp = |key| {
+            let timestamp = getter(key)?;
+            DateTime::parse_from_rfc2822(&timestamp)
+                .map(|timestamp| timestamp.timestamp())
+                .map_err(|e| Error::InvalidObjectMetadata(format!("invalid {} header: {}", key, e)))
+        };
+        Ok(Self {
+            timestamp: parse_timestamp("date")?,
+            content_type: getter("content-type")?,
+            last_modified: parse_timestamp("last-modified")?,
+            etag: getter("etag")?,
+            storage_account: getter("x-amz-meta-storage-account-pubkey")?,
+            storage_owner: getter("x-amz-meta-owner-account-pubkey")?,
+            content_length: getter("content-length")
+                .ok()
+                .and_then(|length| length.parse().ok()),
+        })
+    }
+}
+
+/// A text file and its metadata, as returned by [`get_text`](crate::ShadowDriveClient::get_text).
+#[derive(Clone, Debug)]
+pub struct TextObject {
+    pub content: String,
+    pub metadata: FileMetadata,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct FileDataResponse {
     pub file_data: FileData,
@@ -209,3 +600,33 @@ pub struct FileData {
 pub struct ListObjectsResponse {
     pub keys: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn file_metadata_reads_account_and_owner_headers() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("date", "Tue, 01 Aug 2023 12:00:00 GMT"),
+            ("content-type", "image/png"),
+            ("last-modified", "Mon, 31 Jul 2023 12:00:00 GMT"),
+            ("etag", "\"abc\""),
+            ("x-amz-meta-storage-account-pubkey", "storage-account"),
+            ("x-amz-meta-owner-account-pubkey", "owner"),
+            ("content-length", "42"),
+        ] {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+
+        let metadata = FileMetadata::from_headers(&headers).unwrap();
+        assert_eq!(metadata.storage_account, "storage-account");
+        assert_eq!(metadata.storage_owner, "owner");
+        assert_eq!(metadata.content_type, "image/png");
+        assert_eq!(metadata.timestamp, 1690891200);
+        assert_eq!(metadata.last_modified, 1690804800);
+        assert_eq!(metadata.content_length, Some(42));
+    }
+}
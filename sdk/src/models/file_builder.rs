@@ -0,0 +1,276 @@
+use bytes::Bytes;
+#[cfg(feature = "compression")]
+use flate2::write::GzEncoder;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+#[cfg(feature = "compression")]
+use std::io::Write;
+use std::path::PathBuf;
+
+#[cfg(any(feature = "compression", feature = "encryption"))]
+use super::payload::Payload;
+use super::ShadowFile;
+#[cfg(feature = "encryption")]
+use crate::encryption::EncryptionKey;
+use crate::{error::Error, models::ShadowDriveResult};
+
+/// How [`ShadowFileBuilder::compression`] compresses a file's contents before upload.
+#[cfg(feature = "compression")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// A gzip stream at the default level, uploaded as `application/gzip`. The object is
+    /// stored and served compressed, so readers decompress it themselves.
+    Gzip,
+}
+
+#[cfg(feature = "compression")]
+impl Compression {
+    fn compress(self, contents: &[u8]) -> ShadowDriveResult<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(contents)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Gzip => "application/gzip",
+        }
+    }
+}
+
+/// Fluent builder for a [`ShadowFile`], created with [`ShadowFile::builder`].
+/// Exactly one source of contents must be set, with [`path`](Self::path) or [`bytes`](Self::bytes).
+/// With the `compression` and `encryption` features, contents can also be compressed and
+/// encrypted before upload, in that order; either reads a file-backed source into memory.
+///
+/// # Example
+///
+/// ```
+/// # use shadow_drive_rust::models::ShadowFile;
+/// let file = ShadowFile::builder()
+///     .path("site/index.html")
+///     .content_type("text/html")
+///     .header("cache-control", "max-age=60")
+///     .build()?;
+/// assert_eq!(file.name(), "index.html");
+/// ```
+#[derive(Debug, Default)]
+pub struct ShadowFileBuilder {
+    name: Option<String>,
+    path: Option<PathBuf>,
+    bytes: Option<Bytes>,
+    content_type: Option<String>,
+    headers: Vec<(String, String)>,
+    #[cfg(feature = "compression")]
+    compression: Option<Compression>,
+    #[cfg(feature = "encryption")]
+    encryption_key: Option<EncryptionKey>,
+}
+
+impl ShadowFileBuilder {
+    /// The name to store the file under. Defaults to the basename of [`path`](Self::path).
+    #[must_use]
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Read the contents from this file during upload.
+    #[must_use]
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Upload these in-memory contents.
+    #[must_use]
+    pub fn bytes<B: Into<Bytes>>(mut self, bytes: B) -> Self {
+        self.bytes = Some(bytes.into());
+        self
+    }
+
+    /// The MIME type to upload the file with, instead of the one inferred from its contents.
+    #[must_use]
+    pub fn content_type<S: Into<String>>(mut self, content_type: S) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Compresses the contents before upload. The upload's content type becomes that of the
+    /// compressed format, unless [`content_type`](Self::content_type) is set.
+    #[cfg(feature = "compression")]
+    #[must_use]
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Encrypts the contents with `key` before upload, after any [`compression`](Self::compression).
+    /// The upload's content type becomes `application/octet-stream`, unless
+    /// [`content_type`](Self::content_type) is set. See [`crate::encryption`] for the format.
+    #[cfg(feature = "encryption")]
+    #[must_use]
+    pub fn encrypt(mut self, key: EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// Adds a header to the file's part of the upload request. Invalid header
+    /// names or values are reported by [`build`](Self::build).
+    #[must_use]
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Creates the [`ShadowFile`]. Fails with [`Error::InvalidShadowFile`] if no contents or
+    /// more than one source of contents were set, if no name was set for in-memory contents,
+    /// or if a header is invalid, and with [`Error::InvalidFileName`] if the name would be
+    /// taken from a path without a UTF-8 basename. Compressing or encrypting a file-backed
+    /// source fails with [`Error::FileSystemError`] if the file can't be read.
+    pub fn build(self) -> ShadowDriveResult<ShadowFile> {
+        let mut file = match (self.path, self.bytes) {
+            (Some(path), None) => match self.name {
+                Some(name) => ShadowFile::file(name, path),
+                None => ShadowFile::try_from(path)?,
+            },
+            (None, Some(bytes)) => {
+                let name = self.name.ok_or_else(|| {
+                    Error::InvalidShadowFile("a name is required for in-memory contents".into())
+                })?;
+                ShadowFile::bytes(name, bytes)
+            }
+            (None, None) => {
+                return Err(Error::InvalidShadowFile(
+                    "no contents set, use `path` or `bytes`".into(),
+                ))
+            }
+            (Some(_), Some(_)) => {
+                return Err(Error::InvalidShadowFile(
+                    "only one of `path` and `bytes` can be set".into(),
+                ))
+            }
+        };
+
+        #[cfg(feature = "compression")]
+        if let Some(compression) = self.compression {
+            let contents = compression.compress(&read_contents(&file)?)?;
+            file.data = Payload::Bytes(contents.into());
+            file.content_type = compression.content_type().to_owned();
+        }
+        #[cfg(feature = "encryption")]
+        if let Some(key) = self.encryption_key {
+            let contents = key.encrypt(&read_contents(&file)?)?;
+            file.data = Payload::Bytes(contents.into());
+            file.content_type = super::FALLBACK_MIMETYPE.to_owned();
+        }
+        if let Some(content_type) = self.content_type {
+            file.content_type = content_type;
+        }
+        file.headers = self
+            .headers
+            .into_iter()
+            .map(|(name, value)| {
+                let header_name = HeaderName::try_from(name.as_str()).map_err(|e| {
+                    Error::InvalidShadowFile(format!("invalid header name {:?}: {}", name, e))
+                })?;
+                let header_value = HeaderValue::try_from(value.as_str()).map_err(|e| {
+                    Error::InvalidShadowFile(format!("invalid value for header {}: {}", name, e))
+                })?;
+                Ok((header_name, header_value))
+            })
+            .collect::<ShadowDriveResult<HeaderMap>>()?;
+        Ok(file)
+    }
+}
+
+/// The contents of a file made by the builder, reading them if it is file-backed.
+#[cfg(any(feature = "compression", feature = "encryption"))]
+fn read_contents(file: &ShadowFile) -> ShadowDriveResult<Vec<u8>> {
+    match &file.data {
+        Payload::Bytes(data) => Ok(data.to_vec()),
+        Payload::File(path) => std::fs::read(path).map_err(Error::FileSystemError),
+        Payload::Stream(_) => Err(Error::Unsupported(
+            "streamed contents can't be compressed or encrypted".into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Error, models::ShadowFile};
+
+    #[test]
+    fn builder_names_files_and_validates_sources() {
+        let file = ShadowFile::builder()
+            .bytes(&b"{}"[..])
+            .name("0.json")
+            .content_type("application/json")
+            .header("cache-control", "no-cache")
+            .build()
+            .unwrap();
+        assert_eq!(file.name(), "0.json");
+        assert_eq!(file.content_type, "application/json");
+        assert_eq!(file.headers["cache-control"], "no-cache");
+
+        let file = ShadowFile::builder().path("images/0.png").build().unwrap();
+        assert_eq!(file.name(), "0.png");
+
+        assert!(matches!(
+            ShadowFile::builder().bytes(&b"{}"[..]).build(),
+            Err(Error::InvalidShadowFile(_))
+        ));
+        assert!(matches!(
+            ShadowFile::builder().name("empty").build(),
+            Err(Error::InvalidShadowFile(_))
+        ));
+        assert!(matches!(
+            ShadowFile::builder()
+                .path("0.json")
+                .header("bad header", "x")
+                .build(),
+            Err(Error::InvalidShadowFile(_))
+        ));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn builder_compresses_contents() {
+        use super::Compression;
+        use std::io::Read;
+
+        let file = ShadowFile::builder()
+            .bytes(&b"hello hello hello"[..])
+            .name("hello.txt")
+            .compression(Compression::Gzip)
+            .build()
+            .unwrap();
+        assert_eq!(file.content_type, "application/gzip");
+        let mut decompressed = String::new();
+        flate2::read::GzDecoder::new(file.as_bytes().unwrap())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, "hello hello hello");
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn builder_encrypts_contents() {
+        use crate::encryption::EncryptionKey;
+
+        let key = EncryptionKey::from([7; 32]);
+        let file = ShadowFile::builder()
+            .bytes(&b"secret"[..])
+            .name("secret.txt")
+            .encrypt(key.clone())
+            .content_type("text/plain")
+            .build()
+            .unwrap();
+        assert_eq!(file.content_type, "text/plain");
+        assert_ne!(file.as_bytes().unwrap(), b"secret");
+        assert_eq!(key.decrypt(file.as_bytes().unwrap()).unwrap(), b"secret");
+    }
+}
@@ -1,5 +1,17 @@
 use bytes::Bytes;
-use std::path::PathBuf;
+use futures::{Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use std::{
+    fmt,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+
+/// A boxed stream of file contents, as produced by [`ShadowFile::from_reader`](super::ShadowFile::from_reader)
+/// and [`ShadowFile::from_async_reader`](super::ShadowFile::from_async_reader).
+pub type ByteStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
 
 /// [`Payload`] is an enum containing the types that the
 /// SDK can upload to ShadowDrive. Each variant is expected to implement [`PayloadExt`] so the SDK
@@ -8,4 +20,73 @@ use std::path::PathBuf;
 pub enum Payload {
     File(PathBuf),
     Bytes(Bytes),
+    Stream(StreamPayload),
+}
+
+/// Contents produced lazily by a reader, with a size declared up front.
+/// The underlying stream can only be uploaded once; clones share it.
+#[derive(Clone)]
+pub struct StreamPayload {
+    stream: Arc<Mutex<Option<ByteStream>>>,
+    size: u64,
+    hasher: Arc<Mutex<Sha256>>,
+}
+
+impl StreamPayload {
+    pub(crate) fn new(stream: ByteStream, size: u64) -> Self {
+        Self {
+            stream: Arc::new(Mutex::new(Some(stream))),
+            size,
+            hasher: Arc::new(Mutex::new(Sha256::new())),
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Takes the stream for upload, hashing its contents as they are sent.
+    /// Returns `None` if the stream was already taken.
+    pub(crate) fn take(&self) -> Option<SyncStream> {
+        let stream = self.stream.lock().ok()?.take()?;
+        let hasher = Arc::clone(&self.hasher);
+        let hashed = stream.inspect(move |chunk| {
+            if let (Ok(chunk), Ok(mut hasher)) = (chunk, hasher.lock()) {
+                hasher.update(chunk);
+            }
+        });
+        Some(SyncStream(Mutex::new(Box::pin(hashed))))
+    }
+
+    /// SHA-256 of the contents, available once the stream has been uploaded.
+    pub(crate) fn digest(&self) -> Option<[u8; 32]> {
+        let taken = self.stream.lock().ok()?.is_none();
+        let hasher = self.hasher.lock().ok()?;
+        taken.then(|| hasher.clone().finalize().into())
+    }
+}
+
+impl fmt::Debug for StreamPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamPayload")
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Makes a [`ByteStream`] `Sync`, as required by [`reqwest::Body::wrap_stream`].
+/// The stream is only ever polled through `&mut self`, so the lock is never contended.
+pub(crate) struct SyncStream(Mutex<ByteStream>);
+
+impl Stream for SyncStream {
+    type Item = std::io::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut()
+            .0
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+            .poll_next(cx)
+    }
 }
@@ -2,6 +2,8 @@ use std::str::FromStr;
 
 use anchor_lang::prelude::Pubkey;
 use serde::{Deserialize, Deserializer};
+
+use crate::StorageAccountVersion;
 #[derive(Clone, Debug, Deserialize)]
 pub struct StorageAccount {
     #[serde(deserialize_with = "deserialize_pubkey")]
@@ -97,7 +99,7 @@ where
     Pubkey::from_str(&s).map_err(serde::de::Error::custom)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 #[serde(tag = "version")]
 pub enum StorageAcct {
     V1(StorageAccount),
@@ -132,3 +134,183 @@ impl StorageAcct {
         }
     }
 }
+
+/// Fields common to every version of storage account.
+pub trait ShadowDriveStorageAccount {
+    /// The address of the account.
+    fn storage_account(&self) -> Pubkey;
+    /// The user-provided name of the account.
+    fn identifier(&self) -> &str;
+    /// Number of bytes of storage reserved by the account.
+    fn storage(&self) -> u64;
+    /// Primary owner of the account.
+    fn owner_1(&self) -> Pubkey;
+    /// Whether the account (and all of its files) is immutable.
+    fn immutable(&self) -> bool;
+    /// Whether the account is marked for deletion.
+    fn to_be_deleted(&self) -> bool;
+    /// Unix timestamp of the account's creation.
+    fn creation_time(&self) -> u32;
+    /// The on-chain version of the account.
+    fn version(&self) -> StorageAccountVersion;
+}
+
+impl ShadowDriveStorageAccount for StorageAccount {
+    fn storage_account(&self) -> Pubkey {
+        self.storage_account
+    }
+    fn identifier(&self) -> &str {
+        &self.identifier
+    }
+    fn storage(&self) -> u64 {
+        self.reserved_bytes
+    }
+    fn owner_1(&self) -> Pubkey {
+        self.owner_1
+    }
+    fn immutable(&self) -> bool {
+        self.immutable
+    }
+    fn to_be_deleted(&self) -> bool {
+        self.to_be_deleted
+    }
+    fn creation_time(&self) -> u32 {
+        self.creation_time
+    }
+    fn version(&self) -> StorageAccountVersion {
+        StorageAccountVersion::V1 {
+            owner_2: Some(self.owner_2).filter(|owner_2| *owner_2 != Pubkey::default()),
+        }
+    }
+}
+
+impl ShadowDriveStorageAccount for StorageAccountV2 {
+    fn storage_account(&self) -> Pubkey {
+        self.storage_account
+    }
+    fn identifier(&self) -> &str {
+        &self.identifier
+    }
+    fn storage(&self) -> u64 {
+        self.reserved_bytes
+    }
+    fn owner_1(&self) -> Pubkey {
+        self.owner_1
+    }
+    fn immutable(&self) -> bool {
+        self.immutable
+    }
+    fn to_be_deleted(&self) -> bool {
+        self.to_be_deleted
+    }
+    fn creation_time(&self) -> u32 {
+        self.creation_time
+    }
+    fn version(&self) -> StorageAccountVersion {
+        StorageAccountVersion::V2
+    }
+}
+
+impl StorageAcct {
+    fn inner(&self) -> &dyn ShadowDriveStorageAccount {
+        match self {
+            StorageAcct::V1(acct) => acct,
+            StorageAcct::V2(acct) => acct,
+        }
+    }
+}
+
+/// A version-agnostic view of a storage account, as returned by
+/// [`get_storage_account`](crate::ShadowDriveClient::get_storage_account).
+///
+/// Use the accessors for fields common to all versions, or
+/// [`as_versioned`](Self::as_versioned) to match on the underlying v1/v2 struct.
+#[derive(Clone, Debug)]
+pub struct StorageAccountInfo {
+    account: StorageAcct,
+}
+
+impl StorageAccountInfo {
+    pub fn storage_account(&self) -> Pubkey {
+        self.account.inner().storage_account()
+    }
+
+    pub fn identifier(&self) -> &str {
+        self.account.inner().identifier()
+    }
+
+    pub fn storage(&self) -> u64 {
+        self.account.inner().storage()
+    }
+
+    pub fn owner_1(&self) -> Pubkey {
+        self.account.inner().owner_1()
+    }
+
+    pub fn immutable(&self) -> bool {
+        self.account.inner().immutable()
+    }
+
+    pub fn is_immutable(&self) -> bool {
+        self.immutable()
+    }
+
+    pub fn to_be_deleted(&self) -> bool {
+        self.account.inner().to_be_deleted()
+    }
+
+    pub fn creation_time(&self) -> u32 {
+        self.account.inner().creation_time()
+    }
+
+    pub fn version(&self) -> StorageAccountVersion {
+        self.account.inner().version()
+    }
+
+    pub fn is_owner(&self, account: Pubkey) -> bool {
+        self.account.is_owner(account)
+    }
+
+    pub fn as_versioned(&self) -> &StorageAcct {
+        &self.account
+    }
+
+    pub fn into_versioned(self) -> StorageAcct {
+        self.account
+    }
+}
+
+impl From<StorageAcct> for StorageAccountInfo {
+    fn from(account: StorageAcct) -> Self {
+        Self { account }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StorageAcct;
+
+    #[test]
+    fn gateway_v2_account_deserializes_as_v2() {
+        let account: StorageAcct = serde_json::from_value(serde_json::json!({
+            "version": "V2",
+            "storage_account": "11111111111111111111111111111111",
+            "reserved_bytes": 1048576,
+            "current_usage": 512,
+            "immutable": false,
+            "to_be_deleted": false,
+            "delete_request_epoch": 0,
+            "owner1": "11111111111111111111111111111111",
+            "account_counter_seed": 0,
+            "creation_time": 0,
+            "creation_epoch": 0,
+            "last_fee_epoch": 0,
+            "identifier": "v2"
+        }))
+        .unwrap();
+        match account {
+            StorageAcct::V2(account) => assert_eq!(account.current_usage, 512),
+            StorageAcct::V1(_) => panic!("v2 account deserialized as v1"),
+        }
+    }
+}
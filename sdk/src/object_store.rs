@@ -0,0 +1,37 @@
+//! A backend-agnostic interface to object storage.
+//!
+//! Code written against [`ObjectStore`] can run on Shadow Drive through
+//! [`ShadowDriveClient`](crate::ShadowDriveClient), on another storage network through its own implementation,
+//! or in tests through [`MemoryObjectStore`](crate::test_utilities::MemoryObjectStore).
+//! Shadow Drive specifics such as staking, storage resizing, and immutability
+//! are only available on the client itself.
+use async_trait::async_trait;
+use bytes::Bytes;
+
+/// Named objects grouped into buckets.
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Identifies a group of objects, e.g. a Shadow Drive storage account.
+    type Bucket: Send + Sync;
+    type Error;
+
+    /// Stores `data` as `name`, replacing any existing object, and returns its URL.
+    async fn put(
+        &self,
+        bucket: &Self::Bucket,
+        name: &str,
+        data: Bytes,
+    ) -> Result<String, Self::Error>;
+
+    /// Returns the contents of the object `name`.
+    async fn get(&self, bucket: &Self::Bucket, name: &str) -> Result<Bytes, Self::Error>;
+
+    /// Deletes the object `name`.
+    async fn delete(&self, bucket: &Self::Bucket, name: &str) -> Result<(), Self::Error>;
+
+    /// Returns the names of all objects in `bucket`.
+    async fn list(&self, bucket: &Self::Bucket) -> Result<Vec<String>, Self::Error>;
+
+    /// Returns whether the object `name` exists.
+    async fn exists(&self, bucket: &Self::Bucket, name: &str) -> Result<bool, Self::Error>;
+}
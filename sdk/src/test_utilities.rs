@@ -0,0 +1,303 @@
+//! Builders for mock on-chain accounts, for unit testing code that reads
+//! storage accounts without a live network.
+//!
+//! Enabled in this crate's tests, or for downstream crates with the
+//! `test-utilities` feature.
+use anchor_lang::AccountSerialize;
+use async_trait::async_trait;
+use bytes::Bytes;
+use shadow_drive_user_staking::instructions::initialize_account::{
+    StorageAccount as StorageAccountV1Data, StorageAccountV2 as StorageAccountV2Data,
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{
+    constants::{PROGRAM_ADDRESS, SHDW_DRIVE_OBJECT_PREFIX},
+    error::Error,
+    models::ShadowDriveResult,
+    object_store::ObjectStore,
+};
+
+/// Lamports given to every mock account.
+const MOCK_LAMPORTS: u64 = 1_000_000_000;
+
+/// Builder for a mock v1 or v2 storage account.
+///
+/// Defaults to a mutable, 1MB account owned by a unique pubkey.
+#[derive(Clone, Debug)]
+pub struct MockStorageAccount {
+    pub owner_1: Pubkey,
+    pub owner_2: Option<Pubkey>,
+    pub storage: u64,
+    pub immutable: bool,
+    pub to_be_deleted: bool,
+    pub delete_request_epoch: u32,
+    pub account_counter_seed: u32,
+    pub creation_time: u32,
+    pub creation_epoch: u32,
+    pub identifier: String,
+}
+
+impl Default for MockStorageAccount {
+    fn default() -> Self {
+        Self {
+            owner_1: Pubkey::new_unique(),
+            owner_2: None,
+            storage: 1 << 20,
+            immutable: false,
+            to_be_deleted: false,
+            delete_request_epoch: 0,
+            account_counter_seed: 0,
+            creation_time: 0,
+            creation_epoch: 0,
+            identifier: "mock".to_string(),
+        }
+    }
+}
+
+impl MockStorageAccount {
+    pub fn owner(mut self, owner_1: Pubkey) -> Self {
+        self.owner_1 = owner_1;
+        self
+    }
+
+    /// Only used by v1 accounts.
+    pub fn owner_2(mut self, owner_2: Pubkey) -> Self {
+        self.owner_2 = Some(owner_2);
+        self
+    }
+
+    pub fn storage(mut self, bytes: u64) -> Self {
+        self.storage = bytes;
+        self
+    }
+
+    pub fn immutable(mut self) -> Self {
+        self.immutable = true;
+        self
+    }
+
+    pub fn marked_for_deletion(mut self, delete_request_epoch: u32) -> Self {
+        self.to_be_deleted = true;
+        self.delete_request_epoch = delete_request_epoch;
+        self
+    }
+
+    pub fn identifier<T: ToString>(mut self, identifier: T) -> Self {
+        self.identifier = identifier.to_string();
+        self
+    }
+
+    /// Builds an [`Account`] holding serialized v1 `StorageAccount` data.
+    pub fn build_v1(&self) -> Account {
+        let account = StorageAccountV1Data {
+            is_static: false,
+            init_counter: 0,
+            del_counter: 0,
+            immutable: self.immutable,
+            to_be_deleted: self.to_be_deleted,
+            delete_request_epoch: self.delete_request_epoch,
+            storage: self.storage,
+            storage_available: self.storage,
+            owner_1: self.owner_1,
+            owner_2: self.owner_2.unwrap_or_default(),
+            shdw_payer: Pubkey::default(),
+            account_counter_seed: self.account_counter_seed,
+            total_cost_of_current_storage: 0,
+            total_fees_paid: 0,
+            creation_time: self.creation_time,
+            creation_epoch: self.creation_epoch,
+            last_fee_epoch: self.creation_epoch,
+            identifier: self.identifier.clone(),
+        };
+        program_account(&account)
+    }
+
+    /// Builds an [`Account`] holding serialized `StorageAccountV2` data.
+    pub fn build_v2(&self) -> Account {
+        let account = StorageAccountV2Data {
+            immutable: self.immutable,
+            to_be_deleted: self.to_be_deleted,
+            delete_request_epoch: self.delete_request_epoch,
+            storage: self.storage,
+            owner_1: self.owner_1,
+            account_counter_seed: self.account_counter_seed,
+            creation_time: self.creation_time,
+            creation_epoch: self.creation_epoch,
+            last_fee_epoch: self.creation_epoch,
+            identifier: self.identifier.clone(),
+        };
+        program_account(&account)
+    }
+}
+
+/// A default mutable v1 storage account.
+pub fn mock_storage_account_v1() -> Account {
+    MockStorageAccount::default().build_v1()
+}
+
+/// A default mutable v2 storage account.
+pub fn mock_storage_account_v2() -> Account {
+    MockStorageAccount::default().build_v2()
+}
+
+/// An immutable v2 storage account of `storage` bytes.
+pub fn mock_immutable_storage_account_v2(storage: u64) -> Account {
+    MockStorageAccount::default()
+        .storage(storage)
+        .immutable()
+        .build_v2()
+}
+
+/// A v2 storage account marked for deletion in `delete_request_epoch`.
+pub fn mock_deleted_storage_account_v2(delete_request_epoch: u32) -> Account {
+    MockStorageAccount::default()
+        .marked_for_deletion(delete_request_epoch)
+        .build_v2()
+}
+
+fn program_account<A: AccountSerialize>(account: &A) -> Account {
+    let mut data = Vec::new();
+    account
+        .try_serialize(&mut data)
+        .expect("failed to serialize mock account");
+    Account {
+        lamports: MOCK_LAMPORTS,
+        data,
+        owner: PROGRAM_ADDRESS,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// An in-memory [`ObjectStore`] with the same bucket and error types as
+/// [`ShadowDriveClient`](crate::ShadowDriveClient), for testing code written against the trait.
+/// Missing objects are reported as a 404 [`Error::ShadowDriveServerError`].
+#[derive(Debug, Default)]
+pub struct MemoryObjectStore {
+    objects: Mutex<BTreeMap<(Pubkey, String), Bytes>>,
+}
+
+impl MemoryObjectStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn objects(&self) -> std::sync::MutexGuard<'_, BTreeMap<(Pubkey, String), Bytes>> {
+        self.objects.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn not_found(name: &str) -> Error {
+    Error::ShadowDriveServerError {
+        status: 404,
+        message: serde_json::Value::String(format!("{} not found", name)),
+    }
+}
+
+#[async_trait]
+impl ObjectStore for MemoryObjectStore {
+    type Bucket = Pubkey;
+    type Error = Error;
+
+    async fn put(&self, bucket: &Pubkey, name: &str, data: Bytes) -> ShadowDriveResult<String> {
+        self.objects().insert((*bucket, name.to_string()), data);
+        Ok(format!("{}/{}/{}", SHDW_DRIVE_OBJECT_PREFIX, bucket, name))
+    }
+
+    async fn get(&self, bucket: &Pubkey, name: &str) -> ShadowDriveResult<Bytes> {
+        self.objects()
+            .get(&(*bucket, name.to_string()))
+            .cloned()
+            .ok_or_else(|| not_found(name))
+    }
+
+    async fn delete(&self, bucket: &Pubkey, name: &str) -> ShadowDriveResult<()> {
+        self.objects()
+            .remove(&(*bucket, name.to_string()))
+            .map(|_| ())
+            .ok_or_else(|| not_found(name))
+    }
+
+    async fn list(&self, bucket: &Pubkey) -> ShadowDriveResult<Vec<String>> {
+        Ok(self
+            .objects()
+            .keys()
+            .filter(|(object_bucket, _)| object_bucket == bucket)
+            .map(|(_, name)| name.clone())
+            .collect())
+    }
+
+    async fn exists(&self, bucket: &Pubkey, name: &str) -> ShadowDriveResult<bool> {
+        Ok(self.objects().contains_key(&(*bucket, name.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::account_version_from_data, StorageAccountVersion};
+
+    #[test]
+    fn mock_accounts_have_expected_versions() {
+        let owner_2 = Pubkey::new_unique();
+        let v1 = MockStorageAccount::default().owner_2(owner_2).build_v1();
+        assert_eq!(
+            account_version_from_data(&v1.data).unwrap(),
+            StorageAccountVersion::V1 {
+                owner_2: Some(owner_2)
+            }
+        );
+
+        for account in [
+            mock_storage_account_v2(),
+            mock_immutable_storage_account_v2(1 << 30),
+            mock_deleted_storage_account_v2(100),
+        ] {
+            assert_eq!(
+                account_version_from_data(&account.data).unwrap(),
+                StorageAccountVersion::V2
+            );
+        }
+    }
+
+    #[test]
+    fn mock_v2_account_round_trips() {
+        use anchor_lang::AccountDeserialize;
+
+        let account = MockStorageAccount::default()
+            .storage(5 << 30)
+            .identifier("round-trip")
+            .marked_for_deletion(42)
+            .build_v2();
+        let decoded = StorageAccountV2Data::try_deserialize(&mut account.data.as_slice()).unwrap();
+        assert_eq!(decoded.storage, 5 << 30);
+        assert_eq!(decoded.identifier, "round-trip");
+        assert!(decoded.to_be_deleted);
+        assert_eq!(decoded.delete_request_epoch, 42);
+    }
+
+    #[test]
+    fn memory_object_store_round_trips() {
+        use futures::executor::block_on;
+
+        let store = MemoryObjectStore::new();
+        let bucket = Pubkey::new_unique();
+        block_on(store.put(&bucket, "a.txt", Bytes::from_static(b"hello"))).unwrap();
+
+        assert!(block_on(store.exists(&bucket, "a.txt")).unwrap());
+        assert_eq!(block_on(store.get(&bucket, "a.txt")).unwrap(), "hello");
+        assert_eq!(block_on(store.list(&bucket)).unwrap(), vec!["a.txt"]);
+        assert!(block_on(store.list(&Pubkey::new_unique()))
+            .unwrap()
+            .is_empty());
+
+        block_on(store.delete(&bucket, "a.txt")).unwrap();
+        assert!(!block_on(store.exists(&bucket, "a.txt")).unwrap());
+        assert!(block_on(store.get(&bucket, "a.txt")).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Parsing of human-readable storage sizes, and SHDW token amounts.
+//!
+//! On-chain storage is priced per GiB (`1 << 30` bytes), so sizes are always
+//! interpreted with binary multiples: `1MB` and `1MiB` both mean 1,048,576 bytes.
+use byte_unit::Byte;
+use std::fmt;
+
+use crate::{error::Error, models::ShadowDriveResult};
+
+/// Parses a size such as `"10MB"`, `"1.5 GiB"` or `"512"` (bytes) into a [`Byte`].
+/// `K`, `M`, `G` and `T` prefixes are binary (powers of 1024) whether or not they are
+/// written with an `i`, matching the GiB used by the Shadow Drive program. Units are case-insensitive.
+///
+/// # Example
+///
+/// ```
+/// # use shadow_drive_rust::units::parse_storage_size;
+/// let size = parse_storage_size("1MB").expect("invalid size");
+/// assert_eq!(size.get_bytes(), 1_048_576);
+/// ```
+pub fn parse_storage_size(size: &str) -> ShadowDriveResult<Byte> {
+    let invalid = || Error::InvalidSizeString(size.to_string());
+
+    let size = size.trim();
+    let unit_start = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (value, unit) = size.split_at(unit_start);
+    let value: f64 = value.parse().map_err(|_| invalid())?;
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return Err(invalid()),
+    };
+
+    let bytes = (value * multiplier as f64).round();
+    if !bytes.is_finite() || bytes < 0.0 || bytes > u64::MAX as f64 {
+        return Err(invalid());
+    }
+    Ok(Byte::from_bytes(bytes as u128))
+}
+
+/// Number of shades, the smallest unit of the SHDW token, in one SHDW.
+pub const SHADES_PER_SHDW: u64 = 1_000_000_000;
+
+/// An amount of SHDW, stored exactly as a number of shades.
+/// `Display` prints the amount in SHDW without rounding, e.g. `1.5 SHDW`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shdw(u64);
+
+impl Shdw {
+    pub fn from_shades(shades: u64) -> Self {
+        Self(shades)
+    }
+
+    pub fn to_shades(self) -> u64 {
+        self.0
+    }
+
+    /// The amount in SHDW, as shown in wallets. May lose precision for large amounts;
+    /// use [`to_shades`](Self::to_shades) for arithmetic.
+    pub fn as_ui(self) -> f64 {
+        self.0 as f64 / SHADES_PER_SHDW as f64
+    }
+}
+
+impl fmt::Display for Shdw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.0 / SHADES_PER_SHDW;
+        let fraction = self.0 % SHADES_PER_SHDW;
+        if fraction == 0 {
+            write!(f, "{} SHDW", whole)
+        } else {
+            let fraction = format!("{:09}", fraction);
+            write!(f, "{}.{} SHDW", whole, fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_and_binary_units_are_binary() {
+        assert_eq!(parse_storage_size("1MB").unwrap().get_bytes(), 1 << 20);
+        assert_eq!(parse_storage_size("1MiB").unwrap().get_bytes(), 1 << 20);
+        assert_eq!(parse_storage_size("2 gb").unwrap().get_bytes(), 2 << 30);
+        assert_eq!(parse_storage_size("1.5KB").unwrap().get_bytes(), 1536);
+        assert_eq!(parse_storage_size("512").unwrap().get_bytes(), 512);
+    }
+
+    #[test]
+    fn rejects_unknown_units() {
+        assert!(parse_storage_size("10XB").is_err());
+        assert!(parse_storage_size("MB").is_err());
+        assert!(parse_storage_size("").is_err());
+    }
+
+    #[test]
+    fn shdw_displays_exact_amount() {
+        assert_eq!(Shdw::from_shades(1_500_000_000).to_string(), "1.5 SHDW");
+        assert_eq!(Shdw::from_shades(2 * SHADES_PER_SHDW).to_string(), "2 SHDW");
+        assert_eq!(Shdw::from_shades(1).to_string(), "0.000000001 SHDW");
+    }
+}